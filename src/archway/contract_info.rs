@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractInfoResponse {
+    pub address: String,
+    #[serde(rename = "contract_info")]
+    pub contract_info: ContractInfo,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractInfo {
+    #[serde(rename = "code_id")]
+    pub code_id: String,
+    pub creator: String,
+    #[serde(default)]
+    pub admin: String,
+    pub label: String,
+}
@@ -1,3 +1,4 @@
+pub mod contract_info;
 pub mod estimate_fees;
 pub mod keys_show;
 pub mod tx_query;
@@ -1,8 +1,23 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::error::WarpError;
+
+/// Event attributes holding the code ID of an upload, in order of preference
+const CODE_ID_ATTRIBUTES: [(&str, &str); 2] = [("store_code", "code_id"), ("message", "code_id")];
+/// Event attributes holding the address of a new contract, in order of preference
+const CONTRACT_ADDRESS_ATTRIBUTES: [(&str, &str); 3] = [
+    ("instantiate", "_contract_address"),
+    ("instantiate", "contract_address"),
+    ("message", "contract_address"),
+];
+
+/// Transaction result as returned by `<daemon> q tx` and the LCD. Every field is
+/// optional so both legacy (SDK <= 0.46) and current (SDK 0.47/0.50) responses
+/// parse; 0.50 drops `logs` entirely and only fills the top-level `events`.
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct TxQueryResponse {
     pub height: String,
     pub txhash: String,
@@ -22,8 +37,61 @@ pub struct TxQueryResponse {
     pub events: Vec<Event>,
 }
 
+impl TxQueryResponse {
+    /// Value of the first `key` attribute of an event of type `event_type`.
+    /// Searches the per-message `logs` first, then the top-level `events`
+    /// (whose attributes are base64-encoded on older Tendermint versions).
+    pub fn find_attribute(&self, event_type: &str, key: &str) -> Option<String> {
+        let from_logs = self
+            .logs
+            .iter()
+            .flat_map(|log| log.events.iter())
+            .filter(|event| event.type_field == event_type)
+            .flat_map(|event| event.attributes.iter())
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.clone());
+        from_logs.or_else(|| {
+            self.events
+                .iter()
+                .filter(|event| event.type_field == event_type)
+                .flat_map(|event| event.attributes.iter())
+                .find_map(|attr| {
+                    if attr.key == key {
+                        return Some(attr.value.clone());
+                    }
+                    let decoded_key = decode_base64(&attr.key)?;
+                    (decoded_key == key).then(|| decode_base64(&attr.value))?
+                })
+        })
+    }
+
+    fn find_any(&self, candidates: &[(&str, &str)]) -> Result<String, WarpError> {
+        candidates
+            .iter()
+            .find_map(|(event_type, key)| self.find_attribute(event_type, key))
+            .ok_or_else(|| {
+                let (event_type, key) = candidates[0];
+                WarpError::TxEventNotFound(format!("{}.{}", event_type, key), self.txhash.clone())
+            })
+    }
+
+    /// Code ID assigned by a `MsgStoreCode` transaction
+    pub fn code_id(&self) -> Result<String, WarpError> {
+        self.find_any(&CODE_ID_ATTRIBUTES)
+    }
+
+    /// Address of the contract created by a `MsgInstantiateContract` transaction
+    pub fn contract_address(&self) -> Result<String, WarpError> {
+        self.find_any(&CONTRACT_ADDRESS_ATTRIBUTES)
+    }
+}
+
+fn decode_base64(value: &str) -> Option<String> {
+    String::from_utf8(STANDARD.decode(value).ok()?).ok()
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Log {
     #[serde(rename = "msg_index")]
     pub msg_index: i64,
@@ -32,7 +100,7 @@ pub struct Log {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Event {
     #[serde(rename = "type")]
     pub type_field: String,
@@ -40,7 +108,7 @@ pub struct Event {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Attribute {
     pub key: String,
     pub value: String,
@@ -48,7 +116,7 @@ pub struct Attribute {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Tx {
     #[serde(rename = "@type")]
     pub type_field: String,
@@ -59,7 +127,7 @@ pub struct Tx {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Body {
     pub messages: Vec<Value>,
     pub memo: String,
@@ -72,19 +140,7 @@ pub struct Body {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Message {
-    #[serde(rename = "@type")]
-    pub type_field: String,
-    pub sender: String,
-    #[serde(rename = "wasm_byte_code")]
-    pub wasm_byte_code: String,
-    pub source: String,
-    pub builder: String,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct AuthInfo {
     #[serde(rename = "signer_infos")]
     pub signer_infos: Vec<SignerInfo>,
@@ -92,7 +148,7 @@ pub struct AuthInfo {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct SignerInfo {
     #[serde(rename = "public_key")]
     pub public_key: PublicKey,
@@ -102,7 +158,7 @@ pub struct SignerInfo {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct PublicKey {
     #[serde(rename = "@type")]
     pub type_field: String,
@@ -110,19 +166,19 @@ pub struct PublicKey {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct ModeInfo {
     pub single: Single,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Single {
     pub mode: String,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Fee {
     pub amount: Vec<Amount>,
     #[serde(rename = "gas_limit")]
@@ -132,8 +188,108 @@ pub struct Fee {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Amount {
     pub denom: String,
     pub amount: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(type_field: &str, attributes: &[(&str, &str)]) -> Event {
+        Event {
+            type_field: type_field.to_owned(),
+            attributes: attributes
+                .iter()
+                .map(|(key, value)| Attribute {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                    index: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn finds_attributes_in_logs_by_type_and_key() {
+        let tx = TxQueryResponse {
+            logs: vec![Log {
+                events: vec![
+                    event("message", &[("action", "/cosmwasm.wasm.v1.MsgStoreCode")]),
+                    event("store_code", &[("code_checksum", "abc"), ("code_id", "42")]),
+                    event("coin_spent", &[("amount", "1uarch")]),
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(tx.code_id().unwrap(), "42");
+        assert!(matches!(
+            tx.contract_address(),
+            Err(WarpError::TxEventNotFound(_, _))
+        ));
+    }
+
+    #[test]
+    fn falls_back_to_top_level_events() {
+        let tx = TxQueryResponse {
+            events: vec![
+                event("wasm", &[("_contract_address", "archway1wrong")]),
+                event(
+                    "instantiate",
+                    &[("_contract_address", "archway1new"), ("code_id", "42")],
+                ),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(tx.contract_address().unwrap(), "archway1new");
+
+        // Tendermint < 0.37 base64-encodes the attributes of top-level events
+        let tx = TxQueryResponse {
+            events: vec![event(
+                "store_code",
+                &[("Y29kZV9pZA==", "Nw=="), ("c2VuZGVy", "YQ==")],
+            )],
+            ..Default::default()
+        };
+        assert_eq!(tx.code_id().unwrap(), "7");
+    }
+
+    #[test]
+    fn supports_secret_network_message_events() {
+        let tx = TxQueryResponse {
+            logs: vec![Log {
+                events: vec![event(
+                    "message",
+                    &[("module", "compute"), ("contract_address", "secret1new")],
+                )],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(tx.contract_address().unwrap(), "secret1new");
+    }
+
+    #[test]
+    fn parses_sdk_050_responses_without_logs() {
+        let tx: TxQueryResponse = serde_json::from_str(
+            r#"{
+                "height": "123",
+                "txhash": "ABC",
+                "code": 0,
+                "gas_wanted": "200000",
+                "gas_used": "150000",
+                "events": [
+                    {"type": "store_code", "attributes": [
+                        {"key": "code_id", "value": "9", "index": true}
+                    ]}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(tx.logs.is_empty());
+        assert_eq!(tx.code_id().unwrap(), "9");
+    }
+}
@@ -1,28 +1,29 @@
-use std::path::PathBuf;
+use std::path::Path;
 
 use serde_json::Value;
 
+use crate::archway::contract_info::ContractInfoResponse;
 use crate::archway::keys_show::KeysShowResponse;
 use crate::archway::tx_query::TxQueryResponse;
+use crate::chains::fees::FeeEstimate;
 use crate::commands::config::NetworkConfig;
 use crate::utils::project_config::{Network, ProjectConfig};
 use crate::WarpError;
 
 pub trait ChainProfile {
     fn get_profile_name(&self) -> String;
-    fn get_common_cli_args<'a, 'b>(
-        &self,
-        tx: bool,
-        network: bool,
-        store: bool,
-        config: &'b ProjectConfig,
-    ) -> Vec<String>;
+    fn get_bech32_prefix(&self) -> String;
+    fn get_hd_path(&self) -> String;
+    fn get_common_cli_args(&self, tx: bool, network: bool, config: &ProjectConfig) -> Vec<String>;
     fn get_key_info(
         &self,
         account_id: &str,
         password: Option<&str>,
         config: &ProjectConfig,
     ) -> Result<KeysShowResponse, WarpError>;
+    /// Uploads `contract`. Like every transaction below, this returns as soon as the
+    /// transaction is broadcast, when only its txhash is known; `query_tx` waits for it
+    /// to be included and returns its events.
     fn store_contract(
         &self,
         contract: &str,
@@ -30,11 +31,13 @@ pub trait ChainProfile {
         password: Option<&str>,
         config: &ProjectConfig,
     ) -> Result<TxQueryResponse, WarpError>;
+    /// Instantiates `code_id`; `admin: None` makes the contract immutable
+    #[allow(clippy::too_many_arguments)]
     fn instantiate_contract(
         &self,
         code_id: &str,
         from: &str,
-        admin: &str,
+        admin: Option<&str>,
         label: &str,
         init_msg: &str,
         coins: Option<String>,
@@ -58,6 +61,29 @@ pub trait ChainProfile {
         password: Option<&str>,
         config: &ProjectConfig,
     ) -> Result<TxQueryResponse, WarpError>;
+    fn update_admin(
+        &self,
+        contract_address: &str,
+        new_admin: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError>;
+    fn clear_admin(
+        &self,
+        contract_address: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError>;
+    /// Expected gas and fee of a transaction. `contract` is the wasm file for code uploads,
+    /// which are simulated ahead of time when `sender` is known.
+    fn estimate_fee(
+        &self,
+        contract: Option<&str>,
+        sender: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<FeeEstimate, WarpError>;
     fn query_tx(&self, tx_hash: &str, config: &ProjectConfig)
         -> Result<TxQueryResponse, WarpError>;
     fn query_contract_smart(
@@ -66,17 +92,27 @@ pub trait ChainProfile {
         query: &str,
         config: &ProjectConfig,
     ) -> Result<Value, WarpError>;
-    fn init_project(&self, dir: &PathBuf) -> Result<(), WarpError>;
+    fn query_contract_info(
+        &self,
+        contract: &str,
+        config: &ProjectConfig,
+    ) -> Result<ContractInfoResponse, WarpError>;
+    /// Hex-encoded SHA-256 of the wasm code stored under `code_id`
+    fn query_code_checksum(
+        &self,
+        code_id: &str,
+        config: &ProjectConfig,
+    ) -> Result<String, WarpError>;
+    fn init_project(&self, dir: &Path) -> Result<(), WarpError>;
     fn new_contract(
         &self,
         contract_name: &str,
-        contract_dir: &PathBuf,
-        project_root: &PathBuf,
+        contract_dir: &Path,
+        project_root: &Path,
     ) -> Result<(), WarpError>;
 
     fn get_node_docker_command(&self, container: Option<String>, config: &ProjectConfig) -> String;
 
-    fn network_params(&self, network_config: &NetworkConfig) -> Network;
-    fn get_initialized_address(&self, tx: &TxQueryResponse) -> String;
-    fn init_frontend(&self, dir: &PathBuf) -> Result<(), WarpError>;
+    fn network_params(&self, network_config: &NetworkConfig) -> Result<Network, WarpError>;
+    fn init_frontend(&self, dir: &Path) -> Result<(), WarpError>;
 }
@@ -0,0 +1,254 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    commands::config::NetworkConfig,
+    error::WarpError,
+    utils::project_config::{FeeConfig, Network, TxBackend, TxConfirmation},
+};
+
+/// Chain definitions shipped with Warp. Files dropped into the project or user
+/// config directory take precedence over these.
+const BUILTIN_DEFINITIONS: [(&str, &str); 3] = [
+    ("archway", include_str!("profiles/archway.toml")),
+    ("xion", include_str!("profiles/xion.toml")),
+    ("scrt", include_str!("profiles/scrt.toml")),
+];
+
+/// Directory (relative to the project root or the user config dir) that holds custom chain definitions
+pub const DEFINITIONS_DIR: &str = "chains";
+
+/// Declarative description of a chain and its tooling
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ChainDefinition {
+    /// Profile name as referenced by `network.profile` in Warp.toml
+    pub name: String,
+    /// Chain daemon binary used for signing and querying (`archwayd`, `xiond`, `secretcli`...)
+    pub daemon: String,
+    /// Name of the CosmWasm module in the daemon CLI (`wasm` or `compute`)
+    #[serde(default = "default_wasm_module")]
+    pub wasm_module: String,
+    /// Subcommand of `<daemon> q <wasm_module>` that performs a smart query
+    #[serde(default = "default_smart_query")]
+    pub smart_query: Vec<String>,
+    /// Subcommand of `<daemon> q <wasm_module>` that returns the contract info
+    #[serde(default = "default_contract_info_query")]
+    pub contract_info_query: Vec<String>,
+    /// Subcommand of `<daemon> q <wasm_module>` that returns the checksum of stored code
+    #[serde(default = "default_code_info_query")]
+    pub code_info_query: Vec<String>,
+    /// Whether contract queries can be sent in plain text to the LCD endpoint
+    #[serde(default = "default_true")]
+    pub lcd_queries: bool,
+    /// Broadcast mode passed to the daemon (`sync`, or `block` on chains older than SDK 0.47)
+    #[serde(default = "default_broadcast_mode")]
+    pub broadcast_mode: String,
+    pub bech32_prefix: String,
+    /// BIP44 derivation path of the account key
+    #[serde(default = "default_hd_path")]
+    pub hd_path: String,
+    /// Whether Warp can sign and broadcast wasm messages for this chain itself
+    #[serde(default = "default_true")]
+    pub native_tx: bool,
+    #[serde(default)]
+    pub gas: GasDefaults,
+    pub templates: TemplateUrls,
+    pub node: NodeDefinition,
+    #[serde(default)]
+    pub networks: HashMap<String, NetworkPreset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct GasDefaults {
+    /// Gas adjustment used for every transaction except code uploads
+    pub adjustment: f64,
+    /// Gas adjustment used for code uploads
+    pub store_adjustment: f64,
+    /// Fallback gas prices if the network does not specify any
+    pub prices: Option<String>,
+    /// Chain-specific gas price estimation (currently only `archway-rewards`)
+    pub estimate: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TemplateUrls {
+    /// Git repository cloned by `warp init`
+    pub project: String,
+    /// Git repository cloned by `warp new`
+    pub contract: String,
+    /// Git repository cloned by `warp frontend`
+    pub frontend: Option<String>,
+    /// Path of the schema generator inside the contract template
+    pub schema_generator: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NodeDefinition {
+    /// Docker image of the local development node
+    pub image: String,
+    /// Extra arguments passed to `docker run`
+    #[serde(default)]
+    pub docker_args: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NetworkPreset {
+    pub chain_id: String,
+    pub rpc_url: String,
+    pub lcd_url: Option<String>,
+    pub denom: String,
+    pub gas_prices: Option<String>,
+}
+
+fn default_wasm_module() -> String {
+    "wasm".to_owned()
+}
+
+fn default_smart_query() -> Vec<String> {
+    vec!["contract-state".to_owned(), "smart".to_owned()]
+}
+
+fn default_contract_info_query() -> Vec<String> {
+    vec!["contract".to_owned()]
+}
+
+fn default_code_info_query() -> Vec<String> {
+    vec!["code-info".to_owned()]
+}
+
+fn default_hd_path() -> String {
+    "m/44'/118'/0'/0/0".to_owned()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_broadcast_mode() -> String {
+    "sync".to_owned()
+}
+
+impl Default for GasDefaults {
+    fn default() -> Self {
+        Self {
+            adjustment: 1.4,
+            store_adjustment: 2.0,
+            prices: None,
+            estimate: None,
+        }
+    }
+}
+
+impl ChainDefinition {
+    /// Looks up the definition for `name` in `<project>/.warp/chains`, then in
+    /// `<config dir>/warp/chains`, and finally among the built-in definitions.
+    pub fn load(name: &str, project_root: Option<&Path>) -> Result<Self, WarpError> {
+        let file_name = format!("{}.toml", name);
+        let candidates = Self::search_dirs(project_root)
+            .into_iter()
+            .map(|dir| dir.join(&file_name));
+        for path in candidates {
+            if path.exists() {
+                let definition: Self = toml::from_str(&fs::read_to_string(&path)?)?;
+                return Ok(definition);
+            }
+        }
+        BUILTIN_DEFINITIONS
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(_, content)| toml::from_str(content))
+            .transpose()?
+            .ok_or_else(|| WarpError::UnknownChainProfile(name.to_owned()))
+    }
+
+    fn search_dirs(project_root: Option<&Path>) -> Vec<PathBuf> {
+        let mut dirs = vec![];
+        if let Some(root) = project_root {
+            dirs.push(root.join(".warp").join(DEFINITIONS_DIR));
+        }
+        if let Some(config) = dirs::config_dir() {
+            dirs.push(config.join("warp").join(DEFINITIONS_DIR));
+        }
+        dirs
+    }
+
+    pub fn network(&self, network_config: &NetworkConfig) -> Result<Network, WarpError> {
+        let key = match network_config {
+            NetworkConfig::Mainnet => "mainnet",
+            NetworkConfig::Testnet => "testnet",
+            NetworkConfig::Local => "local",
+        };
+        let preset = self
+            .networks
+            .get(key)
+            .ok_or_else(|| WarpError::NetworkNotSupported(self.name.clone(), key.to_owned()))?;
+        Ok(Network {
+            profile: self.name.clone(),
+            chain_id: preset.chain_id.clone(),
+            rpc_url: preset.rpc_url.clone(),
+            lcd_url: preset.lcd_url.clone(),
+            denom: preset.denom.clone(),
+            gas_prices: preset.gas_prices.clone(),
+            tx_backend: TxBackend::default(),
+            confirmation: TxConfirmation::default(),
+            fees: FeeConfig::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_definitions_parse() {
+        for (name, _) in BUILTIN_DEFINITIONS.iter() {
+            let definition = ChainDefinition::load(name, None).unwrap();
+            assert_eq!(&definition.name, name);
+            assert!(definition.network(&NetworkConfig::Testnet).is_ok());
+        }
+    }
+
+    #[test]
+    fn project_definition_takes_precedence() {
+        let root = std::env::temp_dir().join(format!("warp-chain-def-{}", std::process::id()));
+        let dir = root.join(".warp").join(DEFINITIONS_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("neutron.toml"),
+            r#"
+            name = "neutron"
+            daemon = "neutrond"
+            bech32_prefix = "neutron"
+
+            [templates]
+            project = "https://example.com/project.git"
+            contract = "https://example.com/contract.git"
+            schema_generator = "src/bin/schema.rs"
+
+            [node]
+            image = "neutron-node"
+
+            [networks.testnet]
+            chain_id = "pion-1"
+            rpc_url = "https://rpc-palvus.pion-1.ntrn.tech:443"
+            denom = "untrn"
+            "#,
+        )
+        .unwrap();
+
+        let definition = ChainDefinition::load("neutron", Some(&root)).unwrap();
+        assert_eq!(definition.daemon, "neutrond");
+        assert_eq!(definition.wasm_module, "wasm");
+        assert_eq!(definition.broadcast_mode, "sync");
+        assert!(definition.network(&NetworkConfig::Mainnet).is_err());
+        assert!(ChainDefinition::load("osmosis", Some(&root)).is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
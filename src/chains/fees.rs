@@ -0,0 +1,231 @@
+use owo_colors::OwoColorize;
+use serde::Serialize;
+
+use crate::{
+    error::WarpError,
+    utils::project_config::{FeeConfig, GasStrategy, PriceStrategy},
+};
+
+use super::{definition::GasDefaults, native};
+
+/// How the gas limit of a single transaction is determined
+#[derive(Clone, Debug, PartialEq)]
+pub enum GasLimit {
+    /// Simulate the transaction and multiply the gas used by the adjustment
+    Simulate(f64),
+    /// Use this gas limit as-is
+    Fixed(u64),
+}
+
+/// Gas limit and gas prices resolved for a single transaction
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fees {
+    pub gas: GasLimit,
+    /// Price per unit of gas such as `0.025uxion`; empty for zero-fee networks
+    pub gas_prices: String,
+}
+
+impl Fees {
+    /// Layers the `[network.fees]` settings over the chain defaults. `estimate` is only
+    /// called when chain-estimated pricing is selected; if it returns `None` the static
+    /// price is used instead, and without one resolving fails rather than sending no price.
+    pub fn resolve<F>(
+        fees: &FeeConfig,
+        network_prices: Option<&str>,
+        defaults: &GasDefaults,
+        store: bool,
+        estimate: F,
+    ) -> Result<Self, WarpError>
+    where
+        F: FnOnce() -> Option<String>,
+    {
+        let gas = match fees.gas.clone().unwrap_or_default() {
+            GasStrategy::Simulate => GasLimit::Simulate(if store {
+                fees.store_gas_adjustment
+                    .unwrap_or(defaults.store_adjustment)
+            } else {
+                fees.gas_adjustment.unwrap_or(defaults.adjustment)
+            }),
+            GasStrategy::Fixed => {
+                GasLimit::Fixed(fees.gas_limit.ok_or(WarpError::MissingGasLimit)?)
+            }
+        };
+
+        let configured = fees.gas_prices.as_deref().or(network_prices);
+        let fallback = configured.or(defaults.prices.as_deref());
+        // Without explicit settings, estimate only if no static price is configured
+        let default_price = if configured.is_none() && defaults.estimate.is_some() {
+            PriceStrategy::Estimate
+        } else {
+            PriceStrategy::Static
+        };
+        let gas_prices = match fees.price.clone().unwrap_or(default_price) {
+            PriceStrategy::Static => fallback.unwrap_or_default().to_owned(),
+            PriceStrategy::Estimate => match (estimate(), fallback) {
+                (Some(estimated), _) => estimated,
+                (None, Some(fallback)) => fallback.to_owned(),
+                (None, None) => return Err(WarpError::GasPriceUnavailable),
+            },
+        };
+        Ok(Self { gas, gas_prices })
+    }
+
+    /// Gas flags for `<daemon> tx ...`
+    pub fn cli_args(&self) -> Vec<String> {
+        let mut args = match &self.gas {
+            GasLimit::Simulate(adjustment) => vec![
+                "--gas".to_owned(),
+                "auto".to_owned(),
+                "--gas-adjustment".to_owned(),
+                adjustment.to_string(),
+            ],
+            GasLimit::Fixed(limit) => vec!["--gas".to_owned(), limit.to_string()],
+        };
+        if !self.gas_prices.is_empty() {
+            args.push("--gas-prices".to_owned());
+            args.push(self.gas_prices.clone());
+        }
+        args
+    }
+}
+
+/// Expected gas and fee of a planned transaction. Values that are only known once
+/// the transaction is simulated at deploy time are `None`.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct FeeEstimate {
+    pub gas_limit: Option<u64>,
+    /// Adjustment applied to the simulated gas, if the gas limit is simulated
+    pub gas_adjustment: Option<f64>,
+    pub gas_prices: String,
+    pub fee: Option<String>,
+}
+
+impl FeeEstimate {
+    /// `gas_used` is the simulated gas, if the transaction could be simulated ahead of time
+    pub fn new(fees: &Fees, gas_used: Option<u64>) -> Result<Self, WarpError> {
+        let (gas_limit, gas_adjustment) = match fees.gas {
+            GasLimit::Fixed(limit) => (Some(limit), None),
+            GasLimit::Simulate(adjustment) => (
+                gas_used.map(|gas| native::adjusted_gas(gas, adjustment)),
+                Some(adjustment),
+            ),
+        };
+        let fee = match gas_limit {
+            Some(_) if fees.gas_prices.is_empty() => Some("0".to_owned()),
+            Some(limit) => Some(native::format_coins(&native::fee_amount(
+                limit,
+                &fees.gas_prices,
+            )?)),
+            None => None,
+        };
+        Ok(Self {
+            gas_limit,
+            gas_adjustment,
+            gas_prices: fees.gas_prices.clone(),
+            fee,
+        })
+    }
+}
+
+/// Prints the fallback notice shown when the chain's fee estimate can't be fetched
+pub fn warn_estimate_failed(error: &WarpError) {
+    println!(
+        "{} {} {}",
+        "Warning!".bright_yellow(),
+        "Could not estimate gas prices, falling back to the configured static price if there is one:"
+            .yellow(),
+        error
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn archway_defaults() -> GasDefaults {
+        GasDefaults {
+            estimate: Some("archway-rewards".to_owned()),
+            prices: Some("1000aarch".to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn estimates_unless_a_static_price_is_configured() {
+        let defaults = archway_defaults();
+        let fees = FeeConfig::default();
+        let resolved = Fees::resolve(&fees, None, &defaults, true, || Some("900aarch".into()));
+        assert_eq!(
+            resolved.unwrap(),
+            Fees {
+                gas: GasLimit::Simulate(2.0),
+                gas_prices: "900aarch".to_owned()
+            }
+        );
+
+        let resolved = Fees::resolve(&fees, Some("5aarch"), &defaults, false, || unreachable!());
+        assert_eq!(resolved.unwrap().gas_prices, "5aarch");
+
+        // The estimate failing falls back to the static price
+        let resolved = Fees::resolve(&fees, None, &defaults, false, || None).unwrap();
+        assert_eq!(resolved.gas_prices, "1000aarch");
+
+        // Without a static price there is nothing to fall back to
+        let defaults = GasDefaults {
+            prices: None,
+            ..archway_defaults()
+        };
+        assert!(matches!(
+            Fees::resolve(&fees, None, &defaults, false, || None),
+            Err(WarpError::GasPriceUnavailable)
+        ));
+    }
+
+    #[test]
+    fn step_overrides_select_a_fixed_gas_limit() {
+        let network = FeeConfig {
+            gas_adjustment: Some(1.2),
+            price: Some(PriceStrategy::Estimate),
+            ..Default::default()
+        };
+        let step = FeeConfig {
+            gas: Some(GasStrategy::Fixed),
+            gas_limit: Some(3_000_000),
+            price: Some(PriceStrategy::Static),
+            gas_prices: Some("0.1uxion".to_owned()),
+            ..Default::default()
+        };
+        let defaults = archway_defaults();
+        let resolved = Fees::resolve(&network.merged(&step), None, &defaults, false, || None);
+        let resolved = resolved.unwrap();
+        assert_eq!(resolved.gas, GasLimit::Fixed(3_000_000));
+        assert_eq!(
+            resolved.cli_args(),
+            vec!["--gas", "3000000", "--gas-prices", "0.1uxion"]
+        );
+
+        let missing_limit = FeeConfig {
+            gas: Some(GasStrategy::Fixed),
+            ..Default::default()
+        };
+        assert!(matches!(
+            Fees::resolve(&missing_limit, None, &defaults, false, || None),
+            Err(WarpError::MissingGasLimit)
+        ));
+    }
+
+    #[test]
+    fn estimates_fees_only_when_the_gas_limit_is_known() {
+        let fees = Fees {
+            gas: GasLimit::Simulate(1.5),
+            gas_prices: "0.025uxion".to_owned(),
+        };
+        let estimate = FeeEstimate::new(&fees, Some(100_000)).unwrap();
+        assert_eq!(estimate.gas_limit, Some(150_000));
+        assert_eq!(estimate.fee.as_deref(), Some("3750uxion"));
+
+        let estimate = FeeEstimate::new(&fees, None).unwrap();
+        assert_eq!(estimate.fee, None);
+        assert_eq!(estimate.gas_adjustment, Some(1.5));
+    }
+}
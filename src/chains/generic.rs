@@ -0,0 +1,733 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs::File,
+    io::Write,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use owo_colors::OwoColorize;
+use serde_json::Value;
+
+use crate::{
+    archway::{
+        contract_info::ContractInfoResponse, estimate_fees::EstimateFeesResponse,
+        keys_show::KeysShowResponse, tx_query::TxQueryResponse,
+    },
+    commands::config::NetworkConfig,
+    error::WarpError,
+    utils::{
+        command_util::CommandWithInput,
+        file_util,
+        keystore::KeyStore,
+        project_config::{Network, ProjectConfig, TxBackend},
+    },
+};
+
+use super::{
+    chain_profile::ChainProfile,
+    definition::ChainDefinition,
+    fees::{self, FeeEstimate, Fees},
+    lcd::LcdClient,
+    native::{self, parse_coins},
+    poll,
+    proto::WasmMsg,
+    signer::Signer,
+};
+
+/// Chain profile driven entirely by a [`ChainDefinition`]
+pub struct GenericProfile {
+    definition: ChainDefinition,
+    /// Gas price estimates per chain ID, fetched at most once per session
+    estimated_prices: RefCell<HashMap<String, Option<String>>>,
+    /// Warp-managed keys unlocked so far, so each is decrypted once per session
+    signers: RefCell<HashMap<String, Signer>>,
+}
+
+impl GenericProfile {
+    pub fn new(definition: ChainDefinition) -> Self {
+        Self {
+            definition,
+            estimated_prices: RefCell::new(HashMap::new()),
+            signers: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn daemon(&self) -> Command {
+        Command::new(&self.definition.daemon)
+    }
+
+    /// LCD client for the configured network, if the chain supports plain-text LCD queries
+    fn lcd(&self, config: &ProjectConfig) -> Option<LcdClient> {
+        if !self.definition.lcd_queries {
+            return None;
+        }
+        config
+            .network
+            .lcd_url
+            .as_deref()
+            .filter(|url| !url.is_empty())
+            .map(LcdClient::new)
+    }
+
+    fn fees(&self, store: bool, config: &ProjectConfig) -> Result<Fees, WarpError> {
+        Fees::resolve(
+            &config.network.fees,
+            config.network.gas_prices.as_deref(),
+            &self.definition.gas,
+            store,
+            || self.estimated_gas_price(config),
+        )
+    }
+
+    /// Chain-estimated gas price, cached for the rest of the session
+    fn estimated_gas_price(&self, config: &ProjectConfig) -> Option<String> {
+        let chain_id = &config.network.chain_id;
+        if let Some(cached) = self.estimated_prices.borrow().get(chain_id) {
+            return cached.clone();
+        }
+        let estimate = match self.definition.gas.estimate.as_deref() {
+            Some("archway-rewards") => self
+                .get_estimated_fee(config)
+                .map(|response| response.get_gas_price())
+                .map_err(|x| fees::warn_estimate_failed(&x))
+                .ok(),
+            _ => None,
+        };
+        self.estimated_prices
+            .borrow_mut()
+            .insert(chain_id.clone(), estimate.clone());
+        estimate
+    }
+
+    fn get_estimated_fee(&self, config: &ProjectConfig) -> Result<EstimateFeesResponse, WarpError> {
+        if let Some(lcd) = self.lcd(config) {
+            return lcd.get("/archway/rewards/v1/estimate_tx_fees?gas_limit=1");
+        }
+        let output = self
+            .daemon()
+            .args(vec!["q", "rewards", "estimate-fees", "1"])
+            .args(self.get_common_cli_args(false, true, config))
+            .stdin(Stdio::inherit())
+            .output()?;
+        let response = serde_json::from_slice::<EstimateFeesResponse>(&output.stdout)?;
+        Ok(response)
+    }
+
+    /// LCD client and signing key, if `from` is a Warp-managed key or the network is
+    /// configured for native signing
+    fn native(
+        &self,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<Option<(LcdClient, Signer)>, WarpError> {
+        let mut keystore = KeyStore::load()?;
+        let managed = keystore.contains(from);
+        if !managed && config.network.tx_backend != TxBackend::Native {
+            return Ok(None);
+        }
+        if !self.definition.native_tx {
+            return Err(WarpError::NativeTxNotSupported(self.get_profile_name()));
+        }
+        let lcd = self.lcd(config).ok_or(WarpError::LcdRequired)?;
+        let signer = if managed {
+            self.unlock(&mut keystore, from, password)?
+        } else {
+            self.env_signer(from)?
+        };
+        Ok(Some((lcd, signer)))
+    }
+
+    fn env_signer(&self, from: &str) -> Result<Signer, WarpError> {
+        Signer::from_env(&self.definition.hd_path, &self.definition.bech32_prefix)?
+            .ok_or_else(|| WarpError::MissingSigningKey(from.to_owned()))
+    }
+
+    /// Decrypts a Warp-managed key, prompting for its password if none was given. The
+    /// key is unlocked once and reused for the rest of the session.
+    fn unlock(
+        &self,
+        keystore: &mut KeyStore,
+        name: &str,
+        password: Option<&str>,
+    ) -> Result<Signer, WarpError> {
+        if let Some(signer) = self.signers.borrow().get(name) {
+            return Ok(signer.clone());
+        }
+        let password = match password {
+            Some(password) => password.to_owned(),
+            None => rpassword::prompt_password(format!("Enter the password for key '{}':", name))?,
+        };
+        let known = keystore
+            .cached_address(
+                name,
+                &self.definition.hd_path,
+                &self.definition.bech32_prefix,
+            )?
+            .is_some();
+        let signer = keystore.signer(
+            name,
+            &password,
+            &self.definition.hd_path,
+            &self.definition.bech32_prefix,
+        )?;
+        // Only a newly cached public key changes the store
+        if !known {
+            keystore.save()?;
+        }
+        self.signers
+            .borrow_mut()
+            .insert(name.to_owned(), signer.clone());
+        Ok(signer)
+    }
+
+    /// Signs `msg` in-process and broadcasts it, see [`ChainProfile::store_contract`]
+    fn send_native(
+        &self,
+        (lcd, signer): (LcdClient, Signer),
+        msg: WasmMsg,
+        store: bool,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError> {
+        let response = native::sign_and_broadcast(
+            &lcd,
+            &signer,
+            &config.network.chain_id,
+            msg,
+            &self.fees(store, config)?,
+        )?;
+        if response.code != 0 {
+            return Err(WarpError::TxFailed(response.txhash, response.raw_log));
+        }
+        Ok(response)
+    }
+
+    /// Runs `<daemon> tx <wasm_module> <args>`, see [`ChainProfile::store_contract`]
+    fn send_tx(
+        &self,
+        args: &[&str],
+        store: bool,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError> {
+        let mut tx = self.daemon();
+        tx.args(["tx", &self.definition.wasm_module])
+            .args(args)
+            .args(self.get_common_cli_args(true, true, config))
+            .args(self.fees(store, config)?.cli_args())
+            .stdout(Stdio::piped())
+            .current_dir(ProjectConfig::find_project_root()?)
+            .stdin(if password.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::inherit()
+            });
+        let json_data = if let Some(pass) = password {
+            tx.call_process_with_input(pass)?.stdout
+        } else {
+            tx.output()?.stdout
+        };
+        let response: TxQueryResponse = serde_json::from_slice(json_data.as_slice())?;
+        if response.code != 0 {
+            return Err(WarpError::TxFailed(response.txhash, response.raw_log));
+        }
+        Ok(response)
+    }
+}
+
+impl ChainProfile for GenericProfile {
+    fn get_profile_name(&self) -> String {
+        self.definition.name.clone()
+    }
+
+    fn get_bech32_prefix(&self) -> String {
+        self.definition.bech32_prefix.clone()
+    }
+
+    fn get_hd_path(&self) -> String {
+        self.definition.hd_path.clone()
+    }
+
+    fn get_common_cli_args(&self, tx: bool, network: bool, config: &ProjectConfig) -> Vec<String> {
+        let mut args = vec!["--output".to_string(), "json".to_string()];
+        if network {
+            args.push("--node".to_string());
+            args.push(config.network.rpc_url.to_string());
+        }
+        if network && tx {
+            args.push("--chain-id".to_string());
+            args.push(config.network.chain_id.to_string());
+        }
+        if tx {
+            let mut tx_args = vec![
+                "-y".to_string(),
+                "-b".to_string(),
+                self.definition.broadcast_mode.clone(),
+            ];
+            args.append(&mut tx_args);
+        }
+        args
+    }
+
+    fn get_key_info(
+        &self,
+        account_id: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<KeysShowResponse, WarpError> {
+        let mut keystore = KeyStore::load()?;
+        let signer = if keystore.contains(account_id) {
+            Some(self.unlock(&mut keystore, account_id, password)?)
+        } else if config.network.tx_backend == TxBackend::Native {
+            Some(self.env_signer(account_id)?)
+        } else {
+            None
+        };
+        if let Some(signer) = signer {
+            return Ok(KeysShowResponse {
+                name: account_id.to_owned(),
+                type_field: "local".to_owned(),
+                address: signer.address.clone(),
+                pubkey: STANDARD.encode(signer.public_key()),
+            });
+        }
+        let mut tx = self.daemon();
+        tx.args(vec!["keys", "show", account_id])
+            .args(self.get_common_cli_args(false, false, config))
+            .stdout(Stdio::piped())
+            .stdin(if password.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::inherit()
+            });
+        let json_data = if let Some(pass) = password {
+            tx.call_process_with_input(pass)?.stdout
+        } else {
+            tx.output()?.stdout
+        };
+        let response: KeysShowResponse = serde_json::from_slice(json_data.as_slice())?;
+        Ok(response)
+    }
+
+    fn store_contract(
+        &self,
+        contract: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError> {
+        if let Some(native) = self.native(from, password, config)? {
+            let msg = WasmMsg::StoreCode {
+                sender: native.1.address.clone(),
+                wasm_byte_code: std::fs::read(ProjectConfig::find_project_root()?.join(contract))?,
+            };
+            return self.send_native(native, msg, true, config);
+        }
+        self.send_tx(&["store", contract, "--from", from], true, password, config)
+    }
+
+    fn instantiate_contract(
+        &self,
+        code_id: &str,
+        from: &str,
+        admin: Option<&str>,
+        label: &str,
+        init_msg: &str,
+        coins: Option<String>,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError> {
+        if let Some(native) = self.native(from, password, config)? {
+            let msg = WasmMsg::InstantiateContract {
+                sender: native.1.address.clone(),
+                admin: admin.unwrap_or_default().to_owned(),
+                code_id: parse_code_id(code_id)?,
+                label: label.to_owned(),
+                msg: init_msg.as_bytes().to_vec(),
+                funds: parse_coins(&coins.unwrap_or_default())?,
+            };
+            return self.send_native(native, msg, false, config);
+        }
+        let coins = coins.unwrap_or_default();
+        let mut args = vec![
+            "instantiate",
+            code_id,
+            init_msg,
+            "--from",
+            from,
+            "--label",
+            label,
+            "--amount",
+            &coins,
+        ];
+        match admin {
+            Some(admin) => args.extend(["--admin", admin]),
+            None => args.push("--no-admin"),
+        }
+        self.send_tx(&args, false, password, config)
+    }
+
+    fn execute_contract(
+        &self,
+        contract_address: &str,
+        msg: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError> {
+        if let Some(native) = self.native(from, password, config)? {
+            let msg = WasmMsg::ExecuteContract {
+                sender: native.1.address.clone(),
+                contract: contract_address.to_owned(),
+                msg: msg.as_bytes().to_vec(),
+                funds: vec![],
+            };
+            return self.send_native(native, msg, false, config);
+        }
+        self.send_tx(
+            &["execute", contract_address, msg, "--from", from],
+            false,
+            password,
+            config,
+        )
+    }
+
+    fn migrate_contract(
+        &self,
+        contract_address: &str,
+        code_id: &str,
+        from: &str,
+        migrate_msg: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError> {
+        if let Some(native) = self.native(from, password, config)? {
+            let msg = WasmMsg::MigrateContract {
+                sender: native.1.address.clone(),
+                contract: contract_address.to_owned(),
+                code_id: parse_code_id(code_id)?,
+                msg: migrate_msg.as_bytes().to_vec(),
+            };
+            return self.send_native(native, msg, false, config);
+        }
+        self.send_tx(
+            &[
+                "migrate",
+                contract_address,
+                code_id,
+                migrate_msg,
+                "--from",
+                from,
+            ],
+            false,
+            password,
+            config,
+        )
+    }
+
+    fn update_admin(
+        &self,
+        contract_address: &str,
+        new_admin: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError> {
+        if let Some(native) = self.native(from, password, config)? {
+            let msg = WasmMsg::UpdateAdmin {
+                sender: native.1.address.clone(),
+                new_admin: new_admin.to_owned(),
+                contract: contract_address.to_owned(),
+            };
+            return self.send_native(native, msg, false, config);
+        }
+        self.send_tx(
+            &[
+                "set-contract-admin",
+                contract_address,
+                new_admin,
+                "--from",
+                from,
+            ],
+            false,
+            password,
+            config,
+        )
+    }
+
+    fn clear_admin(
+        &self,
+        contract_address: &str,
+        from: &str,
+        password: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError> {
+        if let Some(native) = self.native(from, password, config)? {
+            let msg = WasmMsg::ClearAdmin {
+                sender: native.1.address.clone(),
+                contract: contract_address.to_owned(),
+            };
+            return self.send_native(native, msg, false, config);
+        }
+        self.send_tx(
+            &["clear-contract-admin", contract_address, "--from", from],
+            false,
+            password,
+            config,
+        )
+    }
+
+    fn estimate_fee(
+        &self,
+        contract: Option<&str>,
+        sender: Option<&str>,
+        config: &ProjectConfig,
+    ) -> Result<FeeEstimate, WarpError> {
+        let fees = self.fees(contract.is_some(), config)?;
+        let gas_used = match (contract, sender, self.lcd(config)) {
+            (Some(contract), Some(sender), Some(lcd)) if self.definition.native_tx => {
+                // Contracts that aren't built yet or fail to simulate are estimated at deploy time
+                std::fs::read(ProjectConfig::find_project_root()?.join(contract))
+                    .ok()
+                    .and_then(|wasm_byte_code| {
+                        let msg = WasmMsg::StoreCode {
+                            sender: sender.to_owned(),
+                            wasm_byte_code,
+                        };
+                        native::simulate(&lcd, sender, msg).ok()
+                    })
+            }
+            _ => None,
+        };
+        FeeEstimate::new(&fees, gas_used)
+    }
+
+    fn query_tx(
+        &self,
+        tx_hash: &str,
+        config: &ProjectConfig,
+    ) -> Result<TxQueryResponse, WarpError> {
+        let settings = &config.network.confirmation;
+        if let Some(lcd) = self.lcd(config) {
+            return poll::wait_for_tx(tx_hash, settings, || match lcd.query_tx(tx_hash) {
+                Err(WarpError::LcdNotFound(_)) => Ok(None),
+                result => result.map(Some),
+            });
+        }
+        poll::wait_for_tx(tx_hash, settings, || {
+            let cmd = self
+                .daemon()
+                .args(vec!["q", "tx", tx_hash])
+                .args(self.get_common_cli_args(false, true, config))
+                .stdin(Stdio::inherit())
+                .output()?;
+            // The daemon reports "tx not found" on stderr until the tx is indexed
+            if cmd.stdout.iter().all(u8::is_ascii_whitespace) {
+                return Ok(None);
+            }
+            Ok(Some(serde_json::from_slice(cmd.stdout.as_slice())?))
+        })
+    }
+
+    fn query_contract_smart(
+        &self,
+        contract: &str,
+        query: &str,
+        config: &ProjectConfig,
+    ) -> Result<Value, WarpError> {
+        if let Some(lcd) = self.lcd(config) {
+            return lcd.query_contract_smart(contract, query);
+        }
+        let cmd = self
+            .daemon()
+            .args(["q", &self.definition.wasm_module])
+            .args(&self.definition.smart_query)
+            .args([contract, query])
+            .args(self.get_common_cli_args(false, true, config))
+            .stdin(Stdio::inherit())
+            .output()?;
+        if !cmd.stderr.is_empty() {
+            let msg = String::from_utf8(cmd.stderr)?;
+            return Err(WarpError::UnderlyingCliError(msg));
+        }
+        let response: Value = serde_json::from_slice(cmd.stdout.as_slice())?;
+        Ok(response)
+    }
+
+    fn query_contract_info(
+        &self,
+        contract: &str,
+        config: &ProjectConfig,
+    ) -> Result<ContractInfoResponse, WarpError> {
+        if let Some(lcd) = self.lcd(config) {
+            return lcd.query_contract_info(contract);
+        }
+        let cmd = self
+            .daemon()
+            .args(["q", &self.definition.wasm_module])
+            .args(&self.definition.contract_info_query)
+            .arg(contract)
+            .args(self.get_common_cli_args(false, true, config))
+            .stdin(Stdio::inherit())
+            .output()?;
+        if !cmd.stderr.is_empty() {
+            let msg = String::from_utf8(cmd.stderr)?;
+            return Err(WarpError::UnderlyingCliError(msg));
+        }
+        let response: ContractInfoResponse = serde_json::from_slice(cmd.stdout.as_slice())?;
+        Ok(response)
+    }
+
+    fn query_code_checksum(
+        &self,
+        code_id: &str,
+        config: &ProjectConfig,
+    ) -> Result<String, WarpError> {
+        if let Some(lcd) = self.lcd(config) {
+            return lcd.query_code_checksum(code_id);
+        }
+        let cmd = self
+            .daemon()
+            .args(["q", &self.definition.wasm_module])
+            .args(&self.definition.code_info_query)
+            .arg(code_id)
+            .args(self.get_common_cli_args(false, true, config))
+            .stdin(Stdio::inherit())
+            .output()?;
+        if !cmd.stderr.is_empty() {
+            let msg = String::from_utf8(cmd.stderr)?;
+            return Err(WarpError::UnderlyingCliError(msg));
+        }
+        // `code-info` prints JSON with a `data_hash`, some daemons print the bare hash
+        let output = String::from_utf8(cmd.stdout)?;
+        let hash = match serde_json::from_str::<Value>(&output) {
+            Ok(Value::Object(info)) => info
+                .get("data_hash")
+                .or_else(|| info.get("code_hash"))
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_owned(),
+            _ => output,
+        };
+        file_util::normalize_checksum(&hash).ok_or(WarpError::UnderlyingCliError(hash))
+    }
+
+    fn init_project(&self, dir: &Path) -> Result<(), WarpError> {
+        println!("Initializing new workspace...");
+        let cmd = Command::new("git")
+            .arg("clone")
+            .arg(&self.definition.templates.project)
+            .arg(dir.as_os_str())
+            .stdout(Stdio::null())
+            .spawn()?
+            .wait()?;
+        if cmd.success() {
+            Ok(())
+        } else {
+            Err(WarpError::InitFailed)
+        }
+    }
+
+    fn new_contract(
+        &self,
+        contract_name: &str,
+        contract_dir: &Path,
+        project_root: &Path,
+    ) -> Result<(), WarpError> {
+        println!("[1/2] Downloading contract files...");
+        std::fs::create_dir_all(contract_dir)?;
+        let clone = std::process::Command::new("git")
+            .args(vec![
+                "clone",
+                "--depth=1",
+                &self.definition.templates.contract,
+                contract_dir.as_os_str().to_str().unwrap(),
+                "-q",
+            ])
+            .spawn()?
+            .wait()?;
+        if !clone.success() {
+            return Err(WarpError::ContractTemplateCloneFailed);
+        }
+
+        std::fs::remove_dir_all(contract_dir.join(".git"))?;
+        std::fs::remove_file(contract_dir.join("README.md"))?;
+        let cargo_path = contract_dir.join("Cargo.toml");
+        file_util::replace_in_file(cargo_path, "<CONTRACT_NAME>", contract_name)?;
+
+        let lib_path = contract_dir.join("src").join("contract.rs");
+        file_util::replace_in_file(lib_path, "<CONTRACT_NAME>", contract_name)?;
+
+        let schema_path = contract_dir.join(&self.definition.templates.schema_generator);
+        file_util::replace_in_file(schema_path, "<CONTRACT_NAME>", contract_name)?;
+
+        let shared_path = project_root.join("packages").join("shared");
+        let msg_path = shared_path.join("src").join(contract_name).join("msg.rs");
+        std::fs::create_dir_all(msg_path.parent().unwrap())?;
+        let mod_path = msg_path.parent().unwrap().join("mod.rs");
+        std::fs::write(msg_path, crate::consts::MSG_FILE)?;
+        std::fs::write(mod_path, "pub mod msg;")?;
+        let lib_path = shared_path.join("src").join("lib.rs");
+        let mut lib_file = File::options().append(true).open(lib_path)?;
+        writeln!(&mut lib_file, "pub mod {};", &contract_name)?;
+        println!("[2/2] Building the workspace...");
+        std::process::Command::new("cargo")
+            .arg("build")
+            .current_dir(project_root)
+            .spawn()?
+            .wait()?;
+        Ok(())
+    }
+
+    fn get_node_docker_command(&self, container: Option<String>, config: &ProjectConfig) -> String {
+        let mut extra_args = self.definition.node.docker_args.join(" ");
+        if !extra_args.is_empty() {
+            extra_args.push(' ');
+        }
+        format!("docker run -it -p 9091:9091 -p 26657:26657 -p 26656:26656 -p 1317:1317 -p 5000:5000 -v {0}:/root/code --name {1} {2}{3}",
+            std::env::current_dir().unwrap().to_str().unwrap(),
+            container.unwrap_or_else(|| config.tests.test_container_name.clone()),
+            extra_args,
+            self.definition.node.image
+        )
+    }
+
+    fn network_params(&self, network_config: &NetworkConfig) -> Result<Network, WarpError> {
+        self.definition.network(network_config)
+    }
+
+    fn init_frontend(&self, dir: &Path) -> Result<(), WarpError> {
+        let url = self
+            .definition
+            .templates
+            .frontend
+            .as_ref()
+            .ok_or_else(|| WarpError::FrontendNotSupported(self.get_profile_name()))?;
+        let mut cmd = Command::new("git")
+            .arg("clone")
+            .arg(url)
+            .current_dir(dir)
+            .stdout(Stdio::null())
+            .spawn()?;
+        let cmd = cmd.wait()?;
+        if !cmd.success() {
+            return Err(WarpError::InitFailed);
+        }
+        println!(
+            "Frontend initialized. - run: {}",
+            "yarn && yarn dev".bright_yellow()
+        );
+
+        Ok(())
+    }
+}
+
+fn parse_code_id(code_id: &str) -> Result<u64, WarpError> {
+    code_id
+        .trim()
+        .parse()
+        .map_err(|_| WarpError::InvalidCodeId(code_id.to_owned()))
+}
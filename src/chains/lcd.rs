@@ -0,0 +1,311 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+
+use crate::{
+    archway::{contract_info::ContractInfoResponse, tx_query::TxQueryResponse},
+    error::WarpError,
+    utils::file_util,
+};
+
+/// Minimal client for the LCD (gRPC-gateway) REST endpoints of a node
+pub struct LcdClient {
+    base_url: String,
+}
+
+#[derive(Deserialize)]
+struct SmartQueryResponse {
+    data: Value,
+}
+
+#[derive(Deserialize)]
+struct GetTxResponse {
+    tx_response: TxQueryResponse,
+}
+
+#[derive(Deserialize)]
+struct CodeResponse {
+    code_info: CodeInfo,
+}
+
+#[derive(Deserialize)]
+struct CodeInfo {
+    data_hash: String,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct SimulateResponse {
+    gas_info: GasInfo,
+}
+
+#[derive(Deserialize)]
+struct GasInfo {
+    gas_used: String,
+}
+
+/// Account number and sequence of an on-chain account
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountInfo {
+    pub account_number: u64,
+    pub sequence: u64,
+}
+
+impl LcdClient {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+        }
+    }
+
+    pub fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, WarpError> {
+        let url = format!("{}{}", self.base_url, path);
+        Self::handle(path, ureq::get(&url).call())
+    }
+
+    fn post<T: DeserializeOwned>(&self, path: &str, body: Value) -> Result<T, WarpError> {
+        let url = format!("{}{}", self.base_url, path);
+        Self::handle(path, ureq::post(&url).send_json(body))
+    }
+
+    fn handle<T: DeserializeOwned>(
+        path: &str,
+        result: Result<ureq::Response, ureq::Error>,
+    ) -> Result<T, WarpError> {
+        match result {
+            Ok(response) => Ok(response.into_json()?),
+            Err(ureq::Error::Status(404, _)) => Err(WarpError::LcdNotFound(path.to_owned())),
+            Err(ureq::Error::Status(code, response)) => {
+                let message = response
+                    .into_json::<ErrorResponse>()
+                    .map(|x| x.message)
+                    .unwrap_or_default();
+                Err(WarpError::LcdError(code, message))
+            }
+            Err(x) => Err(WarpError::LcdTransportError(x.to_string())),
+        }
+    }
+
+    pub fn query_contract_smart(&self, contract: &str, query: &str) -> Result<Value, WarpError> {
+        // Normalize the query so the node receives compact JSON
+        let query: Value = serde_json::from_str(query)?;
+        let encoded = STANDARD.encode(serde_json::to_vec(&query)?);
+        let response: SmartQueryResponse = self.get(&format!(
+            "/cosmwasm/wasm/v1/contract/{}/smart/{}",
+            contract,
+            encode_path_segment(&encoded)
+        ))?;
+        // Some gateways render the raw contract response as a base64 string
+        match response.data {
+            Value::String(data) => {
+                let bytes = STANDARD
+                    .decode(data)
+                    .map_err(|x| WarpError::LcdTransportError(x.to_string()))?;
+                Ok(serde_json::from_slice(&bytes)?)
+            }
+            data => Ok(data),
+        }
+    }
+
+    pub fn query_tx(&self, tx_hash: &str) -> Result<TxQueryResponse, WarpError> {
+        let response: GetTxResponse = self.get(&format!("/cosmos/tx/v1beta1/txs/{}", tx_hash))?;
+        Ok(response.tx_response)
+    }
+
+    pub fn query_contract_info(&self, contract: &str) -> Result<ContractInfoResponse, WarpError> {
+        self.get(&format!("/cosmwasm/wasm/v1/contract/{}", contract))
+    }
+
+    /// Checksum of the wasm code stored under `code_id`, as reported by the node
+    pub fn query_code_checksum(&self, code_id: &str) -> Result<String, WarpError> {
+        let response: CodeResponse = self.get(&format!("/cosmwasm/wasm/v1/code/{}", code_id))?;
+        file_util::normalize_checksum(&response.code_info.data_hash)
+            .ok_or(WarpError::LcdTransportError(response.code_info.data_hash))
+    }
+
+    pub fn query_account(&self, address: &str) -> Result<AccountInfo, WarpError> {
+        let response: Value = self.get(&format!("/cosmos/auth/v1beta1/accounts/{}", address))?;
+        // Vesting and module accounts wrap the base account, so search for it
+        fn find(value: &Value) -> Option<AccountInfo> {
+            let number = |x: &Value| match x {
+                Value::String(s) => s.parse().ok(),
+                x => x.as_u64(),
+            };
+            if let Some(account_number) = value.get("account_number").and_then(number) {
+                return Some(AccountInfo {
+                    account_number,
+                    sequence: value.get("sequence").and_then(number).unwrap_or(0),
+                });
+            }
+            value.as_object()?.values().find_map(find)
+        }
+        find(&response).ok_or_else(|| WarpError::LcdNotFound(address.to_owned()))
+    }
+
+    /// Simulates a signed (or signature-less) transaction and returns the gas used
+    pub fn simulate(&self, tx_bytes: &[u8]) -> Result<u64, WarpError> {
+        let response: SimulateResponse = self.post(
+            "/cosmos/tx/v1beta1/simulate",
+            json!({ "tx_bytes": STANDARD.encode(tx_bytes) }),
+        )?;
+        response
+            .gas_info
+            .gas_used
+            .parse()
+            .map_err(|_| WarpError::LcdTransportError(response.gas_info.gas_used))
+    }
+
+    /// Broadcasts a signed transaction and returns as soon as it passes `CheckTx`
+    pub fn broadcast_tx_sync(&self, tx_bytes: &[u8]) -> Result<TxQueryResponse, WarpError> {
+        let response: GetTxResponse = self.post(
+            "/cosmos/tx/v1beta1/txs",
+            json!({ "tx_bytes": STANDARD.encode(tx_bytes), "mode": "BROADCAST_MODE_SYNC" }),
+        )?;
+        Ok(response.tx_response)
+    }
+}
+
+fn encode_path_segment(segment: &str) -> String {
+    segment
+        .replace('+', "%2B")
+        .replace('/', "%2F")
+        .replace('=', "%3D")
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+    };
+
+    use super::*;
+
+    /// Serves `responses` (status, body) in order and reports the requested paths
+    pub(crate) fn mock_lcd(responses: Vec<(u16, String)>) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(len) = header.to_lowercase().strip_prefix("content-length:") {
+                        content_length = len.trim().parse().unwrap();
+                    }
+                }
+                let mut request_body = vec![0; content_length];
+                std::io::Read::read_exact(&mut reader, &mut request_body).unwrap();
+                let path = request_line.split(' ').nth(1).unwrap().to_owned();
+                sender
+                    .send(format!(
+                        "{} {}",
+                        path,
+                        String::from_utf8(request_body).unwrap()
+                    ))
+                    .unwrap();
+                write!(
+                    stream,
+                    "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+        (url, receiver)
+    }
+
+    #[test]
+    fn smart_query_encodes_query_and_decodes_data() {
+        let (url, requests) = mock_lcd(vec![
+            (200, r#"{"data":{"owner":"archway1abc"}}"#.to_owned()),
+            (200, r#"{"data":"eyJjb3VudCI6NX0="}"#.to_owned()),
+        ]);
+        let client = LcdClient::new(&url);
+
+        let result = client
+            .query_contract_smart("archway1contract", r#"{ "get_owner": {} }"#)
+            .unwrap();
+        assert_eq!(result["owner"], "archway1abc");
+        let path = requests.recv().unwrap();
+        assert_eq!(
+            path.trim(),
+            "/cosmwasm/wasm/v1/contract/archway1contract/smart/eyJnZXRfb3duZXIiOnt9fQ%3D%3D"
+        );
+
+        let result = client
+            .query_contract_smart("archway1contract", r#"{"count":{}}"#)
+            .unwrap();
+        assert_eq!(result["count"], 5);
+    }
+
+    #[test]
+    fn tx_and_contract_info_queries() {
+        let (url, _requests) = mock_lcd(vec![
+            (
+                200,
+                r#"{"tx":{},"tx_response":{"height":"10","txhash":"ABC","codespace":"","code":0,"data":"","raw_log":"","logs":[],"info":"","gas_wanted":"100","gas_used":"90","timestamp":"","events":[]}}"#.to_owned(),
+            ),
+            (
+                200,
+                r#"{"address":"archway1contract","contract_info":{"code_id":"7","creator":"archway1me","admin":"archway1me","label":"acl","created":null,"ibc_port_id":"","extension":null}}"#.to_owned(),
+            ),
+            (404, r#"{"code":5,"message":"tx not found"}"#.to_owned()),
+        ]);
+        let client = LcdClient::new(&url);
+
+        assert_eq!(client.query_tx("ABC").unwrap().gas_used, "90");
+        let info = client.query_contract_info("archway1contract").unwrap();
+        assert_eq!(info.contract_info.code_id, "7");
+        assert!(matches!(
+            client.query_tx("DEF"),
+            Err(WarpError::LcdNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn account_simulate_and_broadcast() {
+        let (url, requests) = mock_lcd(vec![
+            (
+                200,
+                r#"{"account":{"@type":"/cosmos.vesting.v1beta1.ContinuousVestingAccount","base_vesting_account":{"base_account":{"address":"archway1me","account_number":"12","sequence":"4"}}}}"#.to_owned(),
+            ),
+            (200, r#"{"gas_info":{"gas_wanted":"0","gas_used":"81234"}}"#.to_owned()),
+            (
+                200,
+                r#"{"tx_response":{"height":"0","txhash":"ABC","codespace":"","code":0,"data":"","raw_log":"","logs":[],"info":"","gas_wanted":"0","gas_used":"0","timestamp":"","events":[]}}"#.to_owned(),
+            ),
+        ]);
+        let client = LcdClient::new(&url);
+
+        assert_eq!(
+            client.query_account("archway1me").unwrap(),
+            AccountInfo {
+                account_number: 12,
+                sequence: 4
+            }
+        );
+        requests.recv().unwrap();
+        assert_eq!(client.simulate(&[1, 2, 3]).unwrap(), 81234);
+        assert!(requests.recv().unwrap().contains(r#""tx_bytes":"AQID""#));
+        assert_eq!(client.broadcast_tx_sync(&[1]).unwrap().txhash, "ABC");
+        assert!(requests.recv().unwrap().contains("BROADCAST_MODE_SYNC"));
+    }
+}
@@ -1,4 +1,24 @@
-pub mod archway;
 pub mod chain_profile;
-pub mod xion;
-pub mod secret;
+pub mod definition;
+pub mod fees;
+pub mod generic;
+pub mod lcd;
+pub mod native;
+pub mod poll;
+pub mod proto;
+pub mod signer;
+
+use std::path::Path;
+
+use crate::error::WarpError;
+
+use self::{chain_profile::ChainProfile, definition::ChainDefinition, generic::GenericProfile};
+
+/// Resolves the chain profile `name` from the available chain definitions
+pub fn load_profile(
+    name: &str,
+    project_root: Option<&Path>,
+) -> Result<Box<dyn ChainProfile>, WarpError> {
+    let definition = ChainDefinition::load(name, project_root)?;
+    Ok(Box::new(GenericProfile::new(definition)))
+}
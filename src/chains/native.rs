@@ -0,0 +1,131 @@
+use regex::Regex;
+
+use crate::{archway::tx_query::TxQueryResponse, error::WarpError};
+
+use super::{
+    fees::{Fees, GasLimit},
+    lcd::LcdClient,
+    proto::{self, Coin, WasmMsg},
+    signer::Signer,
+};
+
+/// Builds, signs and broadcasts `msg` without the chain daemon
+pub fn sign_and_broadcast(
+    lcd: &LcdClient,
+    signer: &Signer,
+    chain_id: &str,
+    msg: WasmMsg,
+    fees: &Fees,
+) -> Result<TxQueryResponse, WarpError> {
+    let account = lcd.query_account(&signer.address)?;
+    let public_key = signer.public_key();
+    let body = proto::tx_body(&[msg], "");
+
+    let gas_limit = match fees.gas {
+        GasLimit::Fixed(limit) => limit,
+        GasLimit::Simulate(adjustment) => {
+            let auth_info = proto::auth_info(&public_key, account.sequence, &[], 0);
+            let gas_used = lcd.simulate(&proto::tx_raw(&body, &auth_info, &[]))?;
+            adjusted_gas(gas_used, adjustment)
+        }
+    };
+    let fee = if fees.gas_prices.is_empty() {
+        vec![]
+    } else {
+        fee_amount(gas_limit, &fees.gas_prices)?
+    };
+
+    let auth_info = proto::auth_info(&public_key, account.sequence, &fee, gas_limit);
+    let sign_doc = proto::sign_doc(&body, &auth_info, chain_id, account.account_number);
+    let signature = signer.sign(&sign_doc);
+    lcd.broadcast_tx_sync(&proto::tx_raw(&body, &auth_info, &signature))
+}
+
+/// Gas used by `msg` when sent from `sender`, simulated without a signature
+pub fn simulate(lcd: &LcdClient, sender: &str, msg: WasmMsg) -> Result<u64, WarpError> {
+    let account = lcd.query_account(sender)?;
+    let body = proto::tx_body(&[msg], "");
+    let auth_info = proto::auth_info(&[], account.sequence, &[], 0);
+    lcd.simulate(&proto::tx_raw(&body, &auth_info, &[]))
+}
+
+pub fn adjusted_gas(gas_used: u64, adjustment: f64) -> u64 {
+    (gas_used as f64 * adjustment).ceil() as u64
+}
+
+/// Formats coins the way the daemons print them, e.g. `5000uxion`
+pub fn format_coins(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(|coin| format!("{}{}", coin.amount, coin.denom))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Parses a coin list such as `100uarch,5ibc/ABC`
+pub fn parse_coins(coins: &str) -> Result<Vec<Coin>, WarpError> {
+    let rx = Regex::new(r"^(\d+)([a-zA-Z][a-zA-Z0-9/:._-]*)$")?;
+    coins
+        .split(',')
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .map(|coin| {
+            let captures = rx
+                .captures(coin)
+                .ok_or_else(|| WarpError::InvalidCoins(coin.to_owned()))?;
+            Ok(Coin {
+                amount: captures[1].to_owned(),
+                denom: captures[2].to_owned(),
+            })
+        })
+        .collect()
+}
+
+/// Computes `ceil(gas_limit * price)` for a gas price such as `0.025uxion`
+pub fn fee_amount(gas_limit: u64, gas_prices: &str) -> Result<Vec<Coin>, WarpError> {
+    let rx = Regex::new(r"^(\d+)(?:\.(\d+))?([a-zA-Z][a-zA-Z0-9/:._-]*)$")?;
+    let captures = rx
+        .captures(gas_prices.trim())
+        .ok_or_else(|| WarpError::InvalidCoins(gas_prices.to_owned()))?;
+    let fraction = captures
+        .get(2)
+        .map_or("", |x| x.as_str())
+        .trim_end_matches('0');
+    let mantissa: u128 = format!("{}{}", &captures[1], fraction)
+        .parse()
+        .map_err(|_| WarpError::InvalidCoins(gas_prices.to_owned()))?;
+    let scale = 10u128.pow(fraction.len() as u32);
+    let amount = (gas_limit as u128 * mantissa).div_ceil(scale);
+    Ok(vec![Coin {
+        denom: captures[3].to_owned(),
+        amount: amount.to_string(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_fees_from_decimal_prices() {
+        assert_eq!(fee_amount(200000, "0.025uxion").unwrap()[0].amount, "5000");
+        assert_eq!(fee_amount(3, "0.5uscrt").unwrap()[0].amount, "2");
+        assert_eq!(
+            fee_amount(100, "900000000000.000000000000000000aconst").unwrap(),
+            vec![Coin {
+                denom: "aconst".to_owned(),
+                amount: "90000000000000".to_owned()
+            }]
+        );
+        assert!(fee_amount(1, "uxion").is_err());
+    }
+
+    #[test]
+    fn parses_coin_lists() {
+        let coins = parse_coins("100uarch, 5ibc/ABC").unwrap();
+        assert_eq!(coins.len(), 2);
+        assert_eq!(coins[1].denom, "ibc/ABC");
+        assert!(parse_coins("").unwrap().is_empty());
+        assert!(parse_coins("arch").is_err());
+    }
+}
@@ -0,0 +1,81 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    archway::tx_query::TxQueryResponse, error::WarpError, utils::project_config::TxConfirmation,
+};
+
+/// Calls `lookup` until it finds the transaction or `settings.timeout_secs` elapse.
+/// `lookup` returns `Ok(None)` while the transaction is not indexed yet; the delay
+/// between attempts grows by `settings.backoff` up to `settings.max_poll_interval_ms`.
+pub fn wait_for_tx<F>(
+    tx_hash: &str,
+    settings: &TxConfirmation,
+    mut lookup: F,
+) -> Result<TxQueryResponse, WarpError>
+where
+    F: FnMut() -> Result<Option<TxQueryResponse>, WarpError>,
+{
+    let deadline = Instant::now() + Duration::from_secs(settings.timeout_secs);
+    let mut interval = settings.poll_interval_ms as f64;
+    loop {
+        if let Some(response) = lookup()? {
+            if response.code != 0 {
+                return Err(WarpError::TxFailed(response.txhash, response.raw_log));
+            }
+            return Ok(response);
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(WarpError::TxTimeout(
+                tx_hash.to_owned(),
+                settings.timeout_secs,
+            ));
+        }
+        let delay = Duration::from_millis(interval as u64).min(deadline - now);
+        std::thread::sleep(delay);
+        interval = (interval * settings.backoff).min(settings.max_poll_interval_ms as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(timeout_secs: u64) -> TxConfirmation {
+        TxConfirmation {
+            timeout_secs,
+            poll_interval_ms: 1,
+            max_poll_interval_ms: 4,
+            backoff: 2.0,
+        }
+    }
+
+    #[test]
+    fn retries_until_the_transaction_is_found() {
+        let mut attempts = 0;
+        let response = wait_for_tx("ABC", &settings(5), || {
+            attempts += 1;
+            Ok((attempts == 3).then(|| TxQueryResponse {
+                txhash: "ABC".to_owned(),
+                ..Default::default()
+            }))
+        })
+        .unwrap();
+        assert_eq!(response.txhash, "ABC");
+        assert_eq!(attempts, 3);
+
+        let failed = wait_for_tx("ABC", &settings(5), || {
+            Ok(Some(TxQueryResponse {
+                code: 5,
+                ..Default::default()
+            }))
+        });
+        assert!(matches!(failed, Err(WarpError::TxFailed(_, _))));
+    }
+
+    #[test]
+    fn gives_up_after_the_timeout() {
+        let result = wait_for_tx("ABC", &settings(0), || Ok(None));
+        assert!(matches!(result, Err(WarpError::TxTimeout(hash, 0)) if hash == "ABC"));
+    }
+}
@@ -0,0 +1,85 @@
+use bech32::{Bech32, Hrp};
+use bip32::{DerivationPath, XPrv};
+use bip39::{Language, Mnemonic};
+use k256::ecdsa::{signature::Signer as _, Signature, SigningKey};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use crate::error::WarpError;
+
+/// Environment variable holding the mnemonic used for native signing
+pub const MNEMONIC_ENV: &str = "WARP_MNEMONIC";
+
+/// In-process secp256k1 key used to sign transactions with SIGN_MODE_DIRECT
+#[derive(Clone)]
+pub struct Signer {
+    key: SigningKey,
+    pub address: String,
+}
+
+impl Signer {
+    pub fn from_mnemonic(phrase: &str, hd_path: &str, prefix: &str) -> Result<Self, WarpError> {
+        let mnemonic = Mnemonic::parse_in_normalized(Language::English, phrase.trim())
+            .map_err(|x| WarpError::InvalidKey(x.to_string()))?;
+        let path: DerivationPath = hd_path
+            .parse()
+            .map_err(|x: bip32::Error| WarpError::InvalidKey(x.to_string()))?;
+        let xprv = XPrv::derive_from_path(mnemonic.to_seed(""), &path)
+            .map_err(|x| WarpError::InvalidKey(x.to_string()))?;
+        let key = xprv.private_key().clone();
+        let address = address(&compressed_public_key(&key), prefix)?;
+        Ok(Self { key, address })
+    }
+
+    /// Reads the mnemonic from the `WARP_MNEMONIC` environment variable
+    pub fn from_env(hd_path: &str, prefix: &str) -> Result<Option<Self>, WarpError> {
+        match std::env::var(MNEMONIC_ENV) {
+            Ok(phrase) if !phrase.trim().is_empty() => {
+                Ok(Some(Self::from_mnemonic(&phrase, hd_path, prefix)?))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    pub fn public_key(&self) -> Vec<u8> {
+        compressed_public_key(&self.key)
+    }
+
+    /// Signs the SHA-256 digest of `sign_doc` and returns the 64-byte `r || s` signature
+    pub fn sign(&self, sign_doc: &[u8]) -> Vec<u8> {
+        let signature: Signature = self.key.sign(sign_doc);
+        signature.to_bytes().to_vec()
+    }
+}
+
+fn compressed_public_key(key: &SigningKey) -> Vec<u8> {
+    key.verifying_key()
+        .to_encoded_point(true)
+        .as_bytes()
+        .to_vec()
+}
+
+/// Bech32 account address of a compressed secp256k1 public key
+pub fn address(public_key: &[u8], prefix: &str) -> Result<String, WarpError> {
+    let hash = Ripemd160::digest(Sha256::digest(public_key));
+    let hrp = Hrp::parse(prefix).map_err(|x| WarpError::InvalidKey(x.to_string()))?;
+    bech32::encode::<Bech32>(hrp, &hash).map_err(|x| WarpError::InvalidKey(x.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn derives_cosmos_address() {
+        let signer = Signer::from_mnemonic(MNEMONIC, "m/44'/118'/0'/0/0", "cosmos").unwrap();
+        assert_eq!(
+            signer.address,
+            "cosmos19rl4cm2hmr8afy4kldpxz3fka4jguq0auqdal4"
+        );
+        assert_eq!(signer.public_key().len(), 33);
+        assert_eq!(signer.sign(b"doc").len(), 64);
+    }
+}
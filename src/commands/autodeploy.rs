@@ -1,16 +1,32 @@
-use std::{path::PathBuf, time::Duration};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    path::{Path, PathBuf},
+};
 
 use crate::{
     chains::chain_profile::ChainProfile,
     commands::BuildCommand,
+    consts::WARP_VERSION,
     error::WarpError,
     executable::Executable,
     utils::{
-        deployment_result::DeploymentResult, deployment_task::DeploymentTask,
-        project_config::ProjectConfig,
+        contract_schema::{ContractSchema, MessageKind},
+        deployment_export::DeploymentExport,
+        deployment_graph::DeploymentGraph,
+        deployment_journal::{DeploymentJournal, JournalAction, JournalEntry},
+        deployment_plan::{DeploymentPlan, PlannedAction, PlannedAfterAction, PlannedStep},
+        deployment_result::{
+            timestamp, ActionRecord, DeploymentRecord, DeploymentResult, MigrationRecord,
+        },
+        deployment_task::DeploymentTask,
+        file_util,
+        keystore::KeyStore,
+        project_config::{AutoDeployStep, ProjectConfig, NO_ADMIN},
+        schema_diff,
+        template::{StepContext, TemplateContext},
     },
 };
-use clap::Args;
+use clap::{Args, ValueEnum};
 use owo_colors::OwoColorize;
 
 #[derive(Args)]
@@ -18,6 +34,30 @@ pub struct AutoDeployCommand {
     #[arg(short, long)]
     /// The name of the new contract
     pub rebuild: bool,
+    /// Show what would be stored, instantiated and migrated without sending any transaction
+    #[arg(long)]
+    pub plan: bool,
+    /// Output format of the deployment plan
+    #[arg(long, value_enum, default_value_t = PlanFormat::Table, requires = "plan")]
+    pub format: PlanFormat,
+    /// Continue a failed deployment, reusing the code IDs and addresses it already has
+    #[arg(long, conflicts_with = "plan")]
+    pub resume: bool,
+    /// Upload every contract, even the ones whose wasm matches the deployed code
+    #[arg(long)]
+    pub force: bool,
+    /// Named environment from Warp.toml to work with
+    #[arg(long)]
+    pub env: Option<String>,
+    /// Don't check messages against the contracts' JSON schemas
+    #[arg(long)]
+    pub skip_schema_check: bool,
+}
+
+#[derive(Clone, PartialEq, Eq, ValueEnum)]
+pub enum PlanFormat {
+    Table,
+    Json,
 }
 
 impl Executable for AutoDeployCommand {
@@ -25,13 +65,13 @@ impl Executable for AutoDeployCommand {
         &self,
         project_root: Option<PathBuf>,
         config: Option<ProjectConfig>,
-        profile: &Box<dyn ChainProfile>,
+        profile: &dyn ChainProfile,
     ) -> Result<(), WarpError> {
         if project_root.is_none() {
             return Err(WarpError::ProjectFileNotFound);
         };
         let project_root = project_root.unwrap();
-        let config = config.unwrap();
+        let config = config.unwrap().with_environment(self.env.as_deref())?;
 
         if config.autodeploy.account_id.is_empty() {
             println!(
@@ -43,6 +83,36 @@ impl Executable for AutoDeployCommand {
             return Ok(());
         }
 
+        let graph = DeploymentGraph::build(&config.autodeploy.steps, &project_root)?;
+        self.check_messages(&config, &project_root)?;
+        if self.plan {
+            let plan = self.plan(&config, &project_root, &graph, profile)?;
+            match self.format {
+                PlanFormat::Table => plan.print_table(),
+                PlanFormat::Json => println!("{}", serde_json::to_string_pretty(&plan)?),
+            }
+            return Ok(());
+        }
+
+        let mut journal = match DeploymentJournal::load(&project_root)? {
+            Some(journal) if self.resume => {
+                if journal.chain_id != config.deployment_key() {
+                    return Err(WarpError::JournalNetworkMismatch(
+                        journal.chain_id,
+                        config.deployment_key(),
+                    ));
+                }
+                journal
+            }
+            Some(_) => {
+                return Err(WarpError::UnfinishedDeployment(DeploymentJournal::path(
+                    &project_root,
+                )))
+            }
+            None if self.resume => return Err(WarpError::NothingToResume),
+            None => DeploymentJournal::start(&project_root, &config.deployment_key()),
+        };
+
         let password =
             rpassword::prompt_password("Enter your keyring password (if using/needed):")?;
         let password = if password.is_empty() {
@@ -53,7 +123,7 @@ impl Executable for AutoDeployCommand {
 
         if self.rebuild {
             BuildCommand { optimized: true }.execute(
-                Some(project_root),
+                Some(project_root.clone()),
                 Some(config.clone()),
                 profile,
             )?;
@@ -65,57 +135,107 @@ impl Executable for AutoDeployCommand {
 
         println!("Deploying from: {}", &deployment_account);
 
+        let mut deployment_file = if DeploymentResult::exists()? {
+            DeploymentResult::parse()?.1
+        } else {
+            DeploymentResult::default()
+        };
+        let current_network = deployment_file.network(&config.deployment_key());
+
         println!("Uploading contracts to the chain...");
         let mut store_txs: Vec<DeploymentTask> = vec![];
         for step in config.autodeploy.steps.iter() {
             print!(" {} {}", "=>".bright_yellow(), step.contract.bright_blue());
-            let response = profile.store_contract(
-                &step.contract,
-                &config.autodeploy.account_id,
-                password,
-                &config,
-            )?;
-            // let full_tx = profile.query_tx(&response.txhash)?;
-            let code_id = response
-                .logs
-                .last()
-                .unwrap()
-                .events
-                .last()
-                .unwrap()
-                .attributes
-                .last()
-                .unwrap()
-                .value
-                .clone();
-            println!(
-                "\t{} ({}) - CODE: {}",
-                "Done.".bright_green(),
-                &response.txhash.bright_blue(),
-                code_id.bright_green()
-            );
-
-            store_txs.push(DeploymentTask {
-                step: &step,
-                code_id: Some(code_id.clone()),
-                contract_address: None,
-            });
-        }
-        // Only add the extra wait if deploying one contract since otherwise it'll be fine anyway
-        if store_txs.len() == 1 {
-            std::thread::sleep(Duration::from_millis(4500));
+            let entry = journal.find(&step.id, JournalAction::Store).cloned();
+            let task = match Self::resumed(&mut journal, entry, profile, &config)? {
+                Some(entry) => {
+                    let code_id = entry.code_id.clone().unwrap_or_default();
+                    println!(
+                        "\t{} - CODE: {}",
+                        "Resumed.".bright_green(),
+                        code_id.bright_green()
+                    );
+                    DeploymentTask {
+                        step,
+                        code_id: Some(code_id),
+                        contract_address: None,
+                        checksum: entry.checksum.clone(),
+                        store_tx: Some(entry.txhash.clone()),
+                        unchanged: false,
+                    }
+                }
+                None => {
+                    let checksum = file_util::sha256_hex(project_root.join(&step.contract))?;
+                    let deployed_code = match self.force {
+                        true => None,
+                        false => Self::unchanged_code(
+                            current_network.get(&step.id),
+                            &checksum,
+                            profile,
+                            &config,
+                        ),
+                    };
+                    if let Some(code_id) = deployed_code {
+                        println!(
+                            "\t{} - CODE: {}",
+                            "Unchanged.".bright_green(),
+                            code_id.bright_green()
+                        );
+                        store_txs.push(DeploymentTask {
+                            step,
+                            code_id: Some(code_id),
+                            contract_address: None,
+                            checksum: Some(checksum),
+                            store_tx: None,
+                            unchanged: true,
+                        });
+                        continue;
+                    }
+                    let response = profile.store_contract(
+                        &step.contract,
+                        &config.autodeploy.account_id,
+                        password,
+                        &config.for_step(step),
+                    )?;
+                    let entry = JournalEntry {
+                        step: step.id.clone(),
+                        action: JournalAction::Store,
+                        txhash: response.txhash.clone(),
+                        code_id: None,
+                        contract_address: None,
+                        checksum: Some(checksum.clone()),
+                        label: None,
+                        action_id: None,
+                        timestamp: timestamp(),
+                        pending: true,
+                    };
+                    journal.record(entry.clone())?;
+                    let code_id = Self::confirm(&mut journal, &entry, profile, &config)?
+                        .code_id
+                        .unwrap_or_default();
+                    println!(
+                        "\t{} ({}) - CODE: {}",
+                        "Done.".bright_green(),
+                        &response.txhash.bright_blue(),
+                        code_id.bright_green()
+                    );
+                    DeploymentTask {
+                        step,
+                        code_id: Some(code_id),
+                        contract_address: None,
+                        checksum: Some(checksum),
+                        store_tx: Some(response.txhash),
+                        unchanged: false,
+                    }
+                }
+            };
+            store_txs.push(task);
         }
         println!("Instantiating uploaded contracts...");
 
-        let deploy_existed = DeploymentResult::exists()?;
-        let mut deployment_file = if deploy_existed {
-            DeploymentResult::parse()?.1
-        } else {
-            DeploymentResult::default()
-        };
-        let current_network = deployment_file.network(&config.network.chain_id);
-
-        for task in config.autodeploy.steps.iter() {
+        // Steps whose contract code changed on chain, for the schema snapshots
+        let mut deployed = vec![];
+        for task in graph.ordered(&config.autodeploy.steps) {
             if task.store_only {
                 println!(
                     " {} {} {}",
@@ -125,105 +245,630 @@ impl Executable for AutoDeployCommand {
                 );
                 continue;
             }
-            let contract_addr: String;
-            if !current_network.contains_key(&task.id) {
-                print!(" {} {}", "=>".bright_yellow(), &task.contract.bright_blue());
-                let init_msg =
-                    Self::format_init_message(&task.init_msg, &store_txs, &deployment_account);
-                let label = if config.autodeploy.make_labels_unique {
-                    let mut l = task.label.clone();
-                    l.push('-');
-                    l.push_str(
-                        &std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs()
-                            .to_string(),
-                    );
-                    l
-                } else {
-                    task.label.clone()
-                };
-                let t = store_txs.iter_mut().find(|x| &x.step.id == &task.id);
-                if t.is_none() {
-                    break;
-                }
-                let t = t.unwrap();
+            let context = Self::message_context(&store_txs, &deployment_account, &config);
+            let t = store_txs.iter_mut().find(|x| x.step.id == task.id);
+            if t.is_none() {
+                break;
+            }
+            let t = t.unwrap();
+            let code_id = t.code_id.clone().unwrap();
+            print!(" {} {}", "=>".bright_yellow(), &task.contract.bright_blue());
+
+            let entry = journal.find(&task.id, JournalAction::Instantiate).cloned();
+            if let Some(entry) = Self::resumed(&mut journal, entry, profile, &config)? {
+                let addr = entry.contract_address.clone().unwrap_or_default();
+                println!("\t{} ({})", "Resumed.".bright_green(), &addr.bright_cyan());
+                t.contract_address = Some(addr.clone());
+                deployed.push(task);
+                current_network.insert(
+                    task.id.clone(),
+                    DeploymentRecord {
+                        address: addr,
+                        code_id: Some(code_id),
+                        checksum: t.checksum.clone(),
+                        label: entry.label.clone(),
+                        admin: Self::resolve_admin(task, &context)?,
+                        deployer: Some(deployment_account.clone()),
+                        store_tx: t.store_tx.clone(),
+                        instantiate_tx: Some(entry.txhash.clone()),
+                        deployed_at: Some(entry.timestamp.clone()),
+                        warp_version: Some(WARP_VERSION.to_owned()),
+                        history: vec![],
+                        actions: vec![],
+                    },
+                );
+            } else if !current_network.contains_key(&task.id) {
+                let init_msg = context.render(&task.init_msg.to_json(&project_root)?)?;
+                let admin = Self::resolve_admin(task, &context)?;
+                self.validate_message(&project_root, task, MessageKind::Instantiate, &init_msg)?;
+                let label = Self::format_label(
+                    task,
+                    &config,
+                    &std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap()
+                        .as_secs()
+                        .to_string(),
+                );
+                let t = store_txs.iter_mut().find(|x| x.step.id == task.id).unwrap();
                 let init_tx = profile.instantiate_contract(
-                    t.code_id.as_ref().unwrap(),
+                    &code_id,
                     &config.autodeploy.account_id,
-                    &deployment_account,
+                    admin.as_deref(),
                     &label,
                     &init_msg,
                     task.coins.clone(),
                     password,
-                    &config,
+                    &config.for_step(task),
                 )?;
-                // let init_full_tx = profile.query_tx(&init_tx)?;
-                let addr = profile.get_initialized_address(&init_tx);
+                let deployed_at = timestamp();
+                let entry = JournalEntry {
+                    step: task.id.clone(),
+                    action: JournalAction::Instantiate,
+                    txhash: init_tx.txhash.clone(),
+                    code_id: Some(code_id.clone()),
+                    contract_address: None,
+                    checksum: t.checksum.clone(),
+                    label: Some(label.clone()),
+                    action_id: None,
+                    timestamp: deployed_at.clone(),
+                    pending: true,
+                };
+                journal.record(entry.clone())?;
+                let addr = Self::confirm(&mut journal, &entry, profile, &config)?
+                    .contract_address
+                    .unwrap_or_default();
                 t.contract_address = Some(addr.clone());
-                contract_addr = addr.clone();
+                deployed.push(task);
                 println!(
                     "\t{} ({}) -- '{}'",
                     "Done.".bright_green(),
                     &addr.bright_cyan(),
                     &init_msg.bright_yellow()
                 );
+                current_network.insert(
+                    task.id.clone(),
+                    DeploymentRecord {
+                        address: addr,
+                        code_id: Some(code_id),
+                        checksum: t.checksum.clone(),
+                        label: Some(label),
+                        admin,
+                        deployer: Some(deployment_account.clone()),
+                        store_tx: t.store_tx.clone(),
+                        instantiate_tx: Some(init_tx.txhash),
+                        deployed_at: Some(deployed_at),
+                        warp_version: Some(WARP_VERSION.to_owned()),
+                        history: vec![],
+                        actions: vec![],
+                    },
+                );
             } else {
-                print!(" {} {}", "=>".bright_yellow(), &task.contract.bright_blue());
+                let record = current_network.get_mut(&task.id).unwrap();
+                let contract_addr = record.address.clone();
+                t.contract_address = Some(contract_addr.clone());
+                if t.unchanged {
+                    println!(
+                        "\t{} ({})",
+                        "Unchanged.".bright_green(),
+                        &contract_addr.bright_cyan()
+                    );
+                    // Records written by older versions learn their code from the chain
+                    record.code_id = Some(code_id);
+                    record.checksum = t.checksum.clone();
+                    continue;
+                }
+                let migrate_msg = Self::message_context(&store_txs, &deployment_account, &config)
+                    .render(&task.migrate_json(&project_root)?)?;
+                self.validate_message(&project_root, task, MessageKind::Migrate, &migrate_msg)?;
+                let t = store_txs.iter().find(|x| x.step.id == task.id).unwrap();
+                let entry = journal.find(&task.id, JournalAction::Migrate).cloned();
+                let (migrate_tx, migrated_at) =
+                    match Self::resumed(&mut journal, entry, profile, &config)? {
+                        Some(entry) => {
+                            println!("\t{}", "Resumed.".bright_green());
+                            (entry.txhash.clone(), entry.timestamp.clone())
+                        }
+                        None => {
+                            let on_chain = profile
+                                .query_contract_info(&contract_addr, &config)
+                                .ok()
+                                .map(|x| x.contract_info.admin);
+                            record.ensure_admin(&task.id, &deployment_account, on_chain)?;
+                            let migrate_tx = profile.migrate_contract(
+                                &contract_addr,
+                                &code_id,
+                                &config.autodeploy.account_id,
+                                &migrate_msg,
+                                password,
+                                &config.for_step(task),
+                            )?;
+                            let migrated_at = timestamp();
+                            let entry = JournalEntry {
+                                step: task.id.clone(),
+                                action: JournalAction::Migrate,
+                                txhash: migrate_tx.txhash.clone(),
+                                code_id: Some(code_id.clone()),
+                                contract_address: Some(contract_addr.clone()),
+                                checksum: t.checksum.clone(),
+                                label: None,
+                                action_id: None,
+                                timestamp: migrated_at.clone(),
+                                pending: true,
+                            };
+                            journal.record(entry.clone())?;
+                            Self::confirm(&mut journal, &entry, profile, &config)?;
+                            println!(
+                                "\t{} (CODE ID: {} => {}) -- '{}'",
+                                "Done.".bright_green(),
+                                &code_id.bright_cyan(),
+                                &contract_addr.bright_cyan(),
+                                &migrate_msg.bright_yellow()
+                            );
+                            (migrate_tx.txhash, migrated_at)
+                        }
+                    };
+                deployed.push(task);
+                record.migrated(MigrationRecord {
+                    from_code_id: record.code_id.clone(),
+                    code_id,
+                    checksum: t.checksum.clone(),
+                    store_tx: t.store_tx.clone(),
+                    migrate_tx,
+                    deployer: Some(deployment_account.clone()),
+                    migrated_at,
+                    warp_version: WARP_VERSION.to_owned(),
+                    rollback: false,
+                });
+            }
+        }
 
-                let t = store_txs.iter_mut().find(|x| &x.step.id == &task.id);
-                if t.is_none() {
-                    break;
+        let actions: Vec<_> = graph
+            .ordered(&config.autodeploy.steps)
+            .flat_map(|step| step.after.iter().enumerate().map(move |x| (step, x)))
+            .collect();
+        if !actions.is_empty() {
+            println!("Running after-deploy actions...");
+        }
+        for (step, (index, action)) in actions {
+            // Targets were checked when building the graph
+            let target = action.target(step, &config.autodeploy.steps).unwrap();
+            let key = action.key(index);
+            let address = current_network[&target.id].address.clone();
+            print!(
+                " {} {} ({} -> {})",
+                "=>".bright_yellow(),
+                step.id.bright_blue(),
+                key,
+                target.id.bright_blue()
+            );
+            let done = current_network[&step.id].action_done(&key, &address);
+            if done && !action.always {
+                println!("\t{}", "Already done.".bright_green());
+                continue;
+            }
+            let entry = journal.find_execute(&step.id, &key).cloned();
+            let (txhash, executed_at) = match Self::resumed(&mut journal, entry, profile, &config)?
+            {
+                Some(entry) => {
+                    println!("\t{}", "Resumed.".bright_green());
+                    (entry.txhash.clone(), entry.timestamp.clone())
                 }
-                let t = t.unwrap();
-                contract_addr = current_network.get(&task.id).unwrap().clone();
-                t.contract_address = Some(contract_addr.clone());
-                let _tx = profile.migrate_contract(
-                    &contract_addr,
-                    &t.code_id.as_ref().unwrap(),
-                    &config.autodeploy.account_id,
-                    &task.migrate_msg.as_ref().unwrap_or(&String::from("{}")),
-                    password,
-                    &config,
-                )?;
-                // let _full_tx = profile.query_tx(&tx.txhash)?;
-                println!(
-                    "\t{} (CODE ID: {} => {}) -- '{}'",
-                    "Done.".bright_green(),
-                    &t.code_id.as_ref().unwrap().bright_cyan(),
-                    &contract_addr.bright_cyan(),
-                    &task
-                        .migrate_msg
-                        .as_ref()
-                        .unwrap_or(&String::from("{}"))
-                        .bright_yellow()
-                );
+                None => {
+                    let msg = Self::message_context(&store_txs, &deployment_account, &config)
+                        .render(&action.msg.to_json(&project_root)?)?;
+                    self.validate_message(&project_root, target, MessageKind::Execute, &msg)?;
+                    let tx = profile.execute_contract(
+                        &address,
+                        &msg,
+                        &config.autodeploy.account_id,
+                        password,
+                        &config.for_step(step),
+                    )?;
+                    let executed_at = timestamp();
+                    let entry = JournalEntry {
+                        step: step.id.clone(),
+                        action: JournalAction::Execute,
+                        txhash: tx.txhash.clone(),
+                        code_id: None,
+                        contract_address: Some(address.clone()),
+                        checksum: None,
+                        label: None,
+                        action_id: Some(key.clone()),
+                        timestamp: executed_at.clone(),
+                        pending: true,
+                    };
+                    journal.record(entry.clone())?;
+                    Self::confirm(&mut journal, &entry, profile, &config)?;
+                    println!(
+                        "\t{} ({}) -- '{}'",
+                        "Done.".bright_green(),
+                        &tx.txhash.bright_blue(),
+                        &msg.bright_yellow()
+                    );
+                    (tx.txhash, executed_at)
+                }
+            };
+            if let Some(record) = current_network.get_mut(&step.id) {
+                record.action_executed(ActionRecord {
+                    id: key,
+                    contract: address,
+                    txhash,
+                    executed_at,
+                });
             }
-            current_network
-                .entry(task.id.clone())
-                .or_insert(contract_addr);
         }
         deployment_file.save()?;
+        journal.finish()?;
+
+        for step in deployed {
+            let contract_dir = ContractSchema::contract_dir(&project_root, &step.contract);
+            if let Some(schema) = ContractSchema::load_dir(&contract_dir, false)? {
+                let name = contract_dir.file_name().unwrap_or_default();
+                schema_diff::snapshot(
+                    &project_root,
+                    &config.deployment_key(),
+                    &name.to_string_lossy(),
+                    &schema,
+                )?;
+            }
+        }
+
+        let export =
+            DeploymentExport::new(&config, deployment_file.network(&config.deployment_key()));
+        for target in config.autodeploy.export.iter() {
+            let path = export.write(target.format, &project_root, &target.out)?;
+            println!("Exported the addresses to {}", path.display().bright_blue());
+        }
         Ok(())
     }
 }
 
 impl AutoDeployCommand {
-    fn format_init_message(
-        init_msg: &str,
+    /// Waits for the broadcast transaction of a pending `entry` and records it as done,
+    /// with the code ID or address it produced
+    fn confirm(
+        journal: &mut DeploymentJournal,
+        entry: &JournalEntry,
+        profile: &dyn ChainProfile,
+        config: &ProjectConfig,
+    ) -> Result<JournalEntry, WarpError> {
+        let tx = profile.query_tx(&entry.txhash, config)?;
+        let confirmed = JournalEntry {
+            code_id: match entry.action {
+                JournalAction::Store => Some(tx.code_id()?),
+                _ => entry.code_id.clone(),
+            },
+            contract_address: match entry.action {
+                JournalAction::Instantiate => Some(tx.contract_address()?),
+                _ => entry.contract_address.clone(),
+            },
+            pending: false,
+            ..entry.clone()
+        };
+        journal.update(confirmed.clone())?;
+        Ok(confirmed)
+    }
+
+    /// The journal `entry` of an earlier run, if its transaction went through. A pending
+    /// one is looked up first; if it failed it's dropped so the transaction is sent again.
+    fn resumed(
+        journal: &mut DeploymentJournal,
+        entry: Option<JournalEntry>,
+        profile: &dyn ChainProfile,
+        config: &ProjectConfig,
+    ) -> Result<Option<JournalEntry>, WarpError> {
+        match entry {
+            Some(entry) if entry.pending => match Self::confirm(journal, &entry, profile, config) {
+                Err(WarpError::TxFailed(..)) => {
+                    journal.discard(&entry)?;
+                    Ok(None)
+                }
+                result => result.map(Some),
+            },
+            entry => Ok(entry),
+        }
+    }
+
+    /// Works out the action, rendered message and fees of every step. Values that only
+    /// exist after the deployment ran (code IDs, new addresses) are shown as placeholders.
+    fn plan(
+        &self,
+        config: &ProjectConfig,
+        project_root: &Path,
+        graph: &DeploymentGraph,
+        profile: &dyn ChainProfile,
+    ) -> Result<DeploymentPlan, WarpError> {
+        let deployer = Self::deployer_address(config, profile);
+        let deployer_display = deployer
+            .clone()
+            .unwrap_or_else(|| "<address:$account_id>".to_owned());
+        let deployments = if DeploymentResult::exists()? {
+            DeploymentResult::parse()?.1
+        } else {
+            DeploymentResult::default()
+        };
+        let existing = deployments
+            .deployment
+            .get(&config.deployment_key())
+            .cloned()
+            .unwrap_or_default();
+
+        let mut tasks: Vec<DeploymentTask> = vec![];
+        for step in config.autodeploy.steps.iter() {
+            let wasm = project_root.join(&step.contract);
+            let deployed_code = match self.force || step.store_only || !wasm.exists() {
+                true => None,
+                false => Self::unchanged_code(
+                    existing.get(&step.id),
+                    &file_util::sha256_hex(wasm)?,
+                    profile,
+                    config,
+                ),
+            };
+            tasks.push(DeploymentTask {
+                step,
+                code_id: Some(
+                    deployed_code
+                        .clone()
+                        .unwrap_or_else(|| format!("<code_id:#{}>", step.reference())),
+                ),
+                contract_address: Some(
+                    existing
+                        .get(&step.id)
+                        .map(|x| x.address.clone())
+                        .unwrap_or_else(|| format!("<address:${}>", step.reference())),
+                ),
+                checksum: None,
+                store_tx: None,
+                unchanged: deployed_code.is_some(),
+            });
+        }
+
+        let mut context = Self::message_context(&tasks, &deployer_display, config);
+        context.lenient = true;
+        let mut steps = vec![];
+        for index in graph.order.iter().copied() {
+            let step = &config.autodeploy.steps[index];
+            let step_config = config.for_step(step);
+            let contract_address = existing.get(&step.id).map(|x| x.address.clone());
+            let action = if step.store_only {
+                PlannedAction::StoreOnly
+            } else if tasks[index].unchanged {
+                PlannedAction::Unchanged
+            } else if contract_address.is_some() {
+                PlannedAction::Migrate
+            } else {
+                PlannedAction::Instantiate
+            };
+            let message = match action {
+                PlannedAction::StoreOnly | PlannedAction::Unchanged => None,
+                PlannedAction::Instantiate => Some(step.init_msg.to_json(project_root)?),
+                PlannedAction::Migrate => Some(step.migrate_json(project_root)?),
+            }
+            .map(|msg| context.render(&msg))
+            .transpose()?;
+            let admin = match action {
+                PlannedAction::Instantiate => Some(
+                    Self::resolve_admin(step, &context)?.unwrap_or_else(|| NO_ADMIN.to_owned()),
+                ),
+                _ => None,
+            };
+            let fee = match action {
+                PlannedAction::StoreOnly | PlannedAction::Unchanged => None,
+                _ => Some(profile.estimate_fee(None, deployer.as_deref(), &step_config)?),
+            };
+            let store_fee = match action {
+                PlannedAction::Unchanged => None,
+                _ => Some(profile.estimate_fee(
+                    Some(&step.contract),
+                    deployer.as_deref(),
+                    &step_config,
+                )?),
+            };
+            let mut after = vec![];
+            for (index, action) in step.after.iter().enumerate() {
+                let target = action.target(step, &config.autodeploy.steps).unwrap();
+                let key = action.key(index);
+                let done = match (existing.get(&step.id), existing.get(&target.id)) {
+                    (Some(record), Some(target)) => record.action_done(&key, &target.address),
+                    _ => false,
+                };
+                after.push(PlannedAfterAction {
+                    id: key,
+                    contract: target.id.clone(),
+                    message: context.render(&action.msg.to_json(project_root)?)?,
+                    skipped: done && !action.always,
+                });
+            }
+            steps.push(PlannedStep {
+                id: step.id.clone(),
+                stage: graph.stages[index],
+                contract: step.contract.clone(),
+                action,
+                label: Self::format_label(step, config, "<timestamp>"),
+                contract_address,
+                admin,
+                message,
+                store_fee,
+                fee,
+                after,
+            });
+        }
+        Ok(DeploymentPlan {
+            chain_id: config.network.chain_id.clone(),
+            environment: config.environment.clone(),
+            deployer: deployer_display,
+            steps,
+        })
+    }
+
+    /// Code ID of the deployed contract if its code has the given checksum. Records
+    /// without a checksum (written by Warp 0.3 and earlier) are checked against the
+    /// code info on chain; any query failure counts as changed.
+    fn unchanged_code(
+        record: Option<&DeploymentRecord>,
+        checksum: &str,
+        profile: &dyn ChainProfile,
+        config: &ProjectConfig,
+    ) -> Option<String> {
+        let record = record?;
+        if let Some(stored) = &record.checksum {
+            return record.code_id.clone().filter(|_| stored == checksum);
+        }
+        let code_id = match &record.code_id {
+            Some(code_id) => code_id.clone(),
+            None => {
+                profile
+                    .query_contract_info(&record.address, config)
+                    .ok()?
+                    .contract_info
+                    .code_id
+            }
+        };
+        let deployed = profile.query_code_checksum(&code_id, config).ok()?;
+        (deployed == checksum).then_some(code_id)
+    }
+
+    /// Deployer address if it is known without asking for a password
+    fn deployer_address(config: &ProjectConfig, profile: &dyn ChainProfile) -> Option<String> {
+        let account_id = &config.autodeploy.account_id;
+        let keystore = KeyStore::load().ok()?;
+        if keystore.contains(account_id) {
+            return keystore
+                .cached_address(
+                    account_id,
+                    &profile.get_hd_path(),
+                    &profile.get_bech32_prefix(),
+                )
+                .ok()
+                .flatten();
+        }
+        profile
+            .get_key_info(account_id, None, config)
+            .ok()
+            .map(|x| x.address)
+    }
+
+    fn format_label(step: &AutoDeployStep, config: &ProjectConfig, suffix: &str) -> String {
+        if config.autodeploy.make_labels_unique {
+            format!("{}-{}", step.label, suffix)
+        } else {
+            step.label.clone()
+        }
+    }
+
+    /// Admin of a new contract, `None` for an immutable one
+    fn resolve_admin(
+        step: &AutoDeployStep,
+        context: &TemplateContext,
+    ) -> Result<Option<String>, WarpError> {
+        let Some(admin) = step.admin_json() else {
+            return Ok(None);
+        };
+        let rendered = context.render(&admin)?;
+        match serde_json::from_str(&rendered) {
+            Ok(serde_json::Value::String(admin)) => Ok(Some(admin)),
+            _ => Err(WarpError::InvalidAdmin(step.id.clone(), rendered)),
+        }
+    }
+
+    /// Template context with the deployer and everything known about the steps so far
+    fn message_context(
         tasks: &[DeploymentTask],
         deployment_account: &str,
-    ) -> String {
-        let mut new_msg = init_msg.replace("$account_id", &deployment_account);
-        tasks.iter().for_each(|x| {
-            new_msg = new_msg
-                .replace(
-                    &format!("${}", &x.step.id),
-                    &x.contract_address.as_ref().unwrap_or(&String::new()),
-                )
-                .replace(&format!("#{}", &x.step.id), &x.code_id.as_ref().unwrap())
-        });
-        new_msg
+        config: &ProjectConfig,
+    ) -> TemplateContext {
+        let mut context = TemplateContext::new(Some(deployment_account), config);
+        for task in tasks.iter() {
+            context.steps.insert(
+                task.step.reference().to_owned(),
+                StepContext {
+                    address: task.contract_address.clone(),
+                    code_id: task.code_id.clone(),
+                    label: Some(task.step.label.clone()),
+                    checksum: task.checksum.clone(),
+                },
+            );
+        }
+        context
+    }
+
+    /// Renders every message with stand-ins for the values that don't exist yet and
+    /// checks it against the contract's schema, so template, JSON and schema errors show
+    /// up before anything is sent. Code IDs stand in as `0` so they stay valid JSON.
+    fn check_messages(&self, config: &ProjectConfig, project_root: &Path) -> Result<(), WarpError> {
+        let mut context = TemplateContext::new(Some("<address:$account_id>"), config);
+        for step in config.autodeploy.steps.iter() {
+            context.steps.insert(
+                step.reference().to_owned(),
+                StepContext {
+                    address: Some(format!("<address:${}>", step.reference())),
+                    code_id: Some("0".to_owned()),
+                    label: Some(step.label.clone()),
+                    checksum: Some(format!("<checksum:#{}>", step.reference())),
+                },
+            );
+        }
+        let mut schemas = HashMap::new();
+        for step in config.autodeploy.steps.iter().filter(|x| !x.store_only) {
+            Self::resolve_admin(step, &context)?;
+            for action in step.after.iter() {
+                let msg = context.render(&action.msg.to_json(project_root)?)?;
+                let target = action.target(step, &config.autodeploy.steps).unwrap();
+                if self.skip_schema_check {
+                    continue;
+                }
+                if let Some(schema) = self.schema(&mut schemas, project_root, &target.contract)? {
+                    schema.validate(MessageKind::Execute, &msg, true)?;
+                }
+            }
+            let init_msg = context.render(&step.init_msg.to_json(project_root)?)?;
+            let migrate_msg = context.render(&step.migrate_json(project_root)?)?;
+            if self.skip_schema_check {
+                continue;
+            }
+            if let Some(schema) = self.schema(&mut schemas, project_root, &step.contract)? {
+                schema.validate(MessageKind::Instantiate, &init_msg, true)?;
+                // Redeploys send `{}` when no migrate message is set, so that is checked too
+                schema.validate(MessageKind::Migrate, &migrate_msg, true)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Schema of the contract built to `wasm`, loaded (and with `--rebuild` regenerated)
+    /// once per run however many steps deploy it
+    fn schema<'a>(
+        &self,
+        schemas: &'a mut HashMap<PathBuf, Option<ContractSchema>>,
+        project_root: &Path,
+        wasm: &str,
+    ) -> Result<Option<&'a ContractSchema>, WarpError> {
+        let schema = match schemas.entry(ContractSchema::contract_dir(project_root, wasm)) {
+            Entry::Occupied(x) => x.into_mut(),
+            Entry::Vacant(x) => {
+                let schema = ContractSchema::load_dir(x.key(), self.rebuild)?;
+                x.insert(schema)
+            }
+        };
+        Ok(schema.as_ref())
+    }
+
+    /// Checks a message right before it's broadcast
+    fn validate_message(
+        &self,
+        project_root: &Path,
+        step: &AutoDeployStep,
+        kind: MessageKind,
+        msg: &str,
+    ) -> Result<(), WarpError> {
+        if self.skip_schema_check {
+            return Ok(());
+        }
+        match ContractSchema::load(project_root, &step.contract, false)? {
+            Some(schema) => schema.validate(kind, msg, false),
+            None => Ok(()),
+        }
     }
 }
@@ -1,6 +1,6 @@
 use std::{path::PathBuf, process::Command};
 
-pub use clap::{arg, Args};
+pub use clap::Args;
 
 use crate::{
     chains::chain_profile::ChainProfile, error::WarpError, executable::Executable,
@@ -19,7 +19,7 @@ impl Executable for BuildCommand {
         &self,
         project_root: Option<PathBuf>,
         config: Option<ProjectConfig>,
-        _profile: &Box<dyn ChainProfile>,
+        _profile: &dyn ChainProfile,
     ) -> Result<(), WarpError> {
         if project_root.is_none() {
             return Err(WarpError::ProjectFileNotFound);
@@ -32,7 +32,7 @@ impl Executable for BuildCommand {
             let cmd_str = match config.tooling.optimizer_backend.as_str() {
                 "cw-optimizoor" => {
                     rename_files = true;
-                    format!("cargo cw-optimizoor .")
+                    "cargo cw-optimizoor .".to_string()
                 }
                 _ => {
                     rename_files = false;
@@ -42,8 +42,8 @@ impl Executable for BuildCommand {
                 }
             };
             let cmd_tokens = cmd_str.split(" ").collect::<Vec<&str>>();
-            let cmd_name = cmd_tokens.get(0).unwrap();
-            let cmd_args = cmd_tokens.iter().skip(1).map(|x| *x).collect::<Vec<&str>>();
+            let cmd_name = cmd_tokens.first().unwrap();
+            let cmd_args = cmd_tokens.iter().skip(1).copied().collect::<Vec<&str>>();
 
             Command::new(cmd_name)
                 .current_dir(&project_root)
@@ -55,8 +55,7 @@ impl Executable for BuildCommand {
                 let dir = std::fs::read_dir(&artifacts)?;
                 // TODO: filter_map()
                 let files_to_rename = dir
-                    .filter(|x| x.is_ok())
-                    .map(|x| x.unwrap())
+                    .flatten()
                     .map(|x| x.file_name())
                     .filter(|x| x.to_str().unwrap().contains("-x86_64"))
                     .collect::<Vec<_>>();
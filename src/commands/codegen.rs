@@ -0,0 +1,162 @@
+use std::{fs, path::PathBuf};
+
+use clap::{Args, Subcommand};
+use owo_colors::OwoColorize;
+
+use crate::{
+    chains::chain_profile::ChainProfile,
+    error::WarpError,
+    executable::Executable,
+    utils::{
+        contract_schema::ContractSchema, project_config::ProjectConfig, rust_codegen, ts_codegen,
+    },
+};
+
+#[derive(Args)]
+pub struct CodegenCommand {
+    /// Codegen subcommand
+    #[command(subcommand)]
+    subcommand: CodegenSubcommand,
+}
+
+#[derive(Subcommand)]
+enum CodegenSubcommand {
+    /// Generate TypeScript types and CosmJS clients from the contracts' JSON schemas
+    Ts(CodegenTsArgs),
+    /// Regenerate the typed Rust interfaces in the shared package from the existing schemas
+    Rust(CodegenRustArgs),
+}
+
+#[derive(Args)]
+struct CodegenRustArgs {
+    /// Contracts to generate code for (directory names under 'contracts/'), all by default
+    contracts: Vec<String>,
+}
+
+#[derive(Args)]
+struct CodegenTsArgs {
+    /// Contracts to generate code for (directory names under 'contracts/'), all by default
+    contracts: Vec<String>,
+    /// Output directory, overriding 'codegen.ts_out' from Warp.toml
+    #[arg(long)]
+    out: Option<PathBuf>,
+    /// Run the schema generators first, even for contracts that have schemas
+    #[arg(long)]
+    regenerate: bool,
+}
+
+impl Executable for CodegenCommand {
+    fn execute(
+        &self,
+        project_root: Option<PathBuf>,
+        config: Option<ProjectConfig>,
+        _profile: &dyn ChainProfile,
+    ) -> Result<(), WarpError> {
+        let project_root = project_root.ok_or(WarpError::ProjectFileNotFound)?;
+        let config = config.unwrap();
+        match &self.subcommand {
+            CodegenSubcommand::Ts(x) => {
+                let out = project_root.join(x.out.as_ref().unwrap_or(&config.codegen.ts_out));
+                let contract_dirs = ContractSchema::contract_dirs(&project_root)?;
+                let names: Vec<String> = contract_dirs
+                    .iter()
+                    .map(|x| {
+                        x.file_name()
+                            .unwrap_or_default()
+                            .to_string_lossy()
+                            .into_owned()
+                    })
+                    .collect();
+                if let Some(unknown) = x.contracts.iter().find(|x| !names.contains(x)) {
+                    return Err(WarpError::ContractIdNotFound(unknown.clone()));
+                }
+
+                // Every schema is loaded before anything is written, so a requested contract
+                // without one fails the run without leaving half of the output updated
+                let mut schemas = vec![];
+                for (contract_dir, name) in contract_dirs.iter().zip(names) {
+                    if !x.contracts.is_empty() && !x.contracts.contains(&name) {
+                        continue;
+                    }
+                    match ContractSchema::load_dir(contract_dir, x.regenerate)? {
+                        Some(schema) => schemas.push((name, schema)),
+                        None if x.contracts.contains(&name) => {
+                            return Err(WarpError::ContractSchemaNotFound(name))
+                        }
+                        None => println!(
+                            " {} {} {}",
+                            "(X)".bright_yellow(),
+                            name.bright_blue(),
+                            "has no schema, skipped.".bright_yellow()
+                        ),
+                    }
+                }
+                for (name, schema) in schemas.iter() {
+                    fs::create_dir_all(&out)?;
+                    let path = out.join(format!("{}.ts", name));
+                    fs::write(&path, ts_codegen::generate(name, schema)?)?;
+                    println!(
+                        " {} {} => {}",
+                        "=>".bright_yellow(),
+                        name.bright_blue(),
+                        path.display()
+                    );
+                }
+
+                // The index exports every module in the directory, including ones from
+                // earlier runs
+                if out.exists() {
+                    let mut modules: Vec<String> = fs::read_dir(&out)?
+                        .flatten()
+                        .filter_map(|x| {
+                            let file_name = x.file_name().to_string_lossy().into_owned();
+                            let module = file_name.strip_suffix(".ts")?;
+                            (module != "index").then(|| module.to_owned())
+                        })
+                        .collect();
+                    modules.sort();
+                    if !modules.is_empty() {
+                        fs::write(out.join("index.ts"), ts_codegen::index(&modules))?;
+                    }
+                }
+            }
+            CodegenSubcommand::Rust(x) => {
+                let mut found = vec![];
+                for contract_dir in ContractSchema::contract_dirs(&project_root)? {
+                    let name = contract_dir
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .into_owned();
+                    if !x.contracts.is_empty() && !x.contracts.contains(&name) {
+                        continue;
+                    }
+                    found.push(name.clone());
+                    let schema = ContractSchema::load_dir(&contract_dir, false)?;
+                    let path = match schema {
+                        Some(schema) => rust_codegen::write(&project_root, &name, &schema)?,
+                        None => None,
+                    };
+                    match path {
+                        Some(path) => println!(
+                            " {} {} => {}",
+                            "=>".bright_yellow(),
+                            name.bright_blue(),
+                            path.display()
+                        ),
+                        None => println!(
+                            " {} {} {}",
+                            "(X)".bright_yellow(),
+                            name.bright_blue(),
+                            "has no schema or shared package module, skipped.".bright_yellow()
+                        ),
+                    }
+                }
+                if let Some(unknown) = x.contracts.iter().find(|x| !found.contains(x)) {
+                    return Err(WarpError::ContractIdNotFound(unknown.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
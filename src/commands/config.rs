@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-pub use clap::{arg, Args};
+pub use clap::Args;
 use clap::{Subcommand, ValueEnum};
 use owo_colors::OwoColorize;
 use serde::{Deserialize, Serialize};
@@ -58,7 +58,7 @@ impl Executable for ConfigCommand {
         &self,
         project_root: Option<PathBuf>,
         config: Option<ProjectConfig>,
-        profile: &Box<dyn ChainProfile>,
+        profile: &dyn ChainProfile,
     ) -> Result<(), WarpError> {
         if project_root.is_none() {
             return Err(WarpError::ProjectFileNotFound);
@@ -96,7 +96,7 @@ impl Executable for ConfigCommand {
         // Network Config
         if let Some(x) = &args.network {
             if modify_values {
-                let params = profile.network_params(&x);
+                let params = profile.network_params(x)?;
                 config.network = params;
             }
             println!(
@@ -0,0 +1,137 @@
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+use owo_colors::OwoColorize;
+
+use crate::{
+    chains::chain_profile::ChainProfile,
+    error::WarpError,
+    executable::Executable,
+    utils::{
+        deployment_result::{DeploymentRecord, DeploymentResult},
+        project_config::ProjectConfig,
+    },
+};
+
+#[derive(Args)]
+pub struct DeploymentsCommand {
+    /// Deployments subcommand
+    #[command(subcommand)]
+    subcommand: DeploymentsSubcommand,
+    /// Named environment from Warp.toml to work with
+    #[arg(long, global = true)]
+    pub env: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum DeploymentsSubcommand {
+    /// List the contracts deployed from this workspace
+    List(DeploymentsListArgs),
+    /// Show the full deployment record of a contract
+    Show(DeploymentsShowArgs),
+}
+
+#[derive(Args)]
+struct DeploymentsListArgs {
+    /// Include every network, not only the one configured in Warp.toml
+    #[arg(short, long)]
+    all: bool,
+}
+
+#[derive(Args)]
+struct DeploymentsShowArgs {
+    /// Contract ID (from the Warp.toml file)
+    id: String,
+    /// Print the record as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+impl Executable for DeploymentsCommand {
+    fn execute(
+        &self,
+        _project_root: Option<PathBuf>,
+        config: Option<ProjectConfig>,
+        _profile: &dyn ChainProfile,
+    ) -> Result<(), WarpError> {
+        let config = config
+            .ok_or(WarpError::ProjectFileNotFound)?
+            .with_environment(self.env.as_deref())?;
+        let (_, mut deployments) = if DeploymentResult::exists()? {
+            DeploymentResult::parse()?
+        } else {
+            (PathBuf::new(), DeploymentResult::default())
+        };
+        let key = &config.deployment_key();
+
+        match &self.subcommand {
+            DeploymentsSubcommand::List(x) => {
+                for (network, records) in deployments.deployment.iter() {
+                    if !x.all && network != key {
+                        continue;
+                    }
+                    println!("{}", network.bright_blue());
+                    for (id, record) in records.iter() {
+                        println!(
+                            " {} {}: {} (code ID: {}, deployed: {})",
+                            "=>".bright_yellow(),
+                            id.bright_blue(),
+                            record.address.bright_cyan(),
+                            record.code_id.as_deref().unwrap_or("?"),
+                            record.deployed_at.as_deref().unwrap_or("?"),
+                        );
+                    }
+                }
+            }
+            DeploymentsSubcommand::Show(x) => {
+                let record = deployments
+                    .network(key)
+                    .get(&x.id)
+                    .ok_or_else(|| WarpError::ContractIdNotFound(x.id.clone()))?;
+                if x.json {
+                    println!("{}", serde_json::to_string_pretty(record)?);
+                } else {
+                    Self::print_record(&x.id, record);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DeploymentsCommand {
+    fn print_record(id: &str, record: &DeploymentRecord) {
+        let field = |name: &str, value: &Option<String>| {
+            println!(
+                "  {:<16}{}",
+                format!("{}:", name),
+                value.as_deref().unwrap_or("-")
+            );
+        };
+        println!("{} {}", id.bright_blue(), record.address.bright_cyan());
+        field("code ID", &record.code_id);
+        field("checksum", &record.checksum);
+        field("label", &record.label);
+        field("admin", &record.admin);
+        field("deployer", &record.deployer);
+        field("store tx", &record.store_tx);
+        field("instantiate tx", &record.instantiate_tx);
+        field("deployed at", &record.deployed_at);
+        field("warp version", &record.warp_version);
+        if record.history.is_empty() {
+            return;
+        }
+        println!("  {}", "migrations:".bright_yellow());
+        for migration in record.history.iter() {
+            println!(
+                "   {} {}: code ID {} => {} (tx {}){}",
+                "=>".bright_yellow(),
+                migration.migrated_at,
+                migration.from_code_id.as_deref().unwrap_or("?"),
+                migration.code_id.bright_green(),
+                migration.migrate_tx,
+                if migration.rollback { " rollback" } else { "" }
+            );
+        }
+    }
+}
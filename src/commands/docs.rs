@@ -0,0 +1,117 @@
+use std::{fs, path::PathBuf};
+
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    chains::chain_profile::ChainProfile,
+    error::WarpError,
+    executable::Executable,
+    utils::{
+        contract_schema::ContractSchema,
+        deployment_result::DeploymentResult,
+        markdown_docs::{self, DeployedInstance},
+        project_config::ProjectConfig,
+    },
+};
+
+#[derive(Args)]
+pub struct DocsCommand {
+    /// Contracts to document (directory names under 'contracts/'), all by default
+    pub contracts: Vec<String>,
+    /// Output directory, overriding 'codegen.docs_out' from Warp.toml
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+    /// Run the schema generators first, even for contracts that have schemas
+    #[arg(long)]
+    pub regenerate: bool,
+}
+
+impl Executable for DocsCommand {
+    fn execute(
+        &self,
+        project_root: Option<PathBuf>,
+        config: Option<ProjectConfig>,
+        _profile: &dyn ChainProfile,
+    ) -> Result<(), WarpError> {
+        let project_root = project_root.ok_or(WarpError::ProjectFileNotFound)?;
+        let config = config.unwrap();
+        let out = project_root.join(self.out.as_ref().unwrap_or(&config.codegen.docs_out));
+        let deployments = match DeploymentResult::exists()? {
+            true => DeploymentResult::parse()?.1,
+            false => DeploymentResult::default(),
+        };
+
+        let mut found = vec![];
+        for contract_dir in ContractSchema::contract_dirs(&project_root)? {
+            let name = contract_dir
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            if !self.contracts.is_empty() && !self.contracts.contains(&name) {
+                continue;
+            }
+            found.push(name.clone());
+            let Some(schema) = ContractSchema::load_dir(&contract_dir, self.regenerate)? else {
+                println!(
+                    " {} {} {}",
+                    "(X)".bright_yellow(),
+                    name.bright_blue(),
+                    "has no schema, skipped.".bright_yellow()
+                );
+                continue;
+            };
+            // Steps deploying this contract, matched through their wasm file names
+            let steps: Vec<&str> = config
+                .autodeploy
+                .steps
+                .iter()
+                .filter(|x| {
+                    ContractSchema::contract_dir(&project_root, &x.contract) == contract_dir
+                })
+                .map(|x| x.id.as_str())
+                .collect();
+            let mut instances = vec![];
+            for (network, records) in deployments.deployment.iter() {
+                for step in steps.iter() {
+                    if let Some(record) = records.get(*step) {
+                        instances.push(DeployedInstance {
+                            network: network.clone(),
+                            step: step.to_string(),
+                            address: record.address.clone(),
+                            code_id: record.code_id.clone(),
+                        });
+                    }
+                }
+            }
+            fs::create_dir_all(&out)?;
+            let path = out.join(format!("{}.md", name));
+            fs::write(&path, markdown_docs::generate(&name, &schema, &instances)?)?;
+            println!(
+                " {} {} => {}",
+                "=>".bright_yellow(),
+                name.bright_blue(),
+                path.display()
+            );
+        }
+        if let Some(unknown) = self.contracts.iter().find(|x| !found.contains(x)) {
+            return Err(WarpError::ContractIdNotFound(unknown.clone()));
+        }
+
+        // The index links every page in the directory, including ones from earlier runs
+        if out.exists() {
+            let mut pages: Vec<String> = fs::read_dir(&out)?
+                .flatten()
+                .filter_map(|x| {
+                    let file_name = x.file_name().to_string_lossy().into_owned();
+                    let page = file_name.strip_suffix(".md")?;
+                    (page != "README").then(|| page.to_owned())
+                })
+                .collect();
+            pages.sort();
+            fs::write(out.join("README.md"), markdown_docs::index(&pages))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    chains::chain_profile::ChainProfile,
+    error::WarpError,
+    executable::Executable,
+    utils::{
+        deployment_export::{DeploymentExport, ExportFormat},
+        deployment_result::DeploymentResult,
+        project_config::ProjectConfig,
+    },
+};
+
+#[derive(Args)]
+pub struct ExportCommand {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+    pub format: ExportFormat,
+    /// Named environment from Warp.toml to work with
+    #[arg(long)]
+    pub env: Option<String>,
+    /// File to write, relative to the project root. Printed to stdout if omitted.
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+}
+
+impl Executable for ExportCommand {
+    fn execute(
+        &self,
+        project_root: Option<PathBuf>,
+        config: Option<ProjectConfig>,
+        _profile: &dyn ChainProfile,
+    ) -> Result<(), WarpError> {
+        let project_root = project_root.ok_or(WarpError::ProjectFileNotFound)?;
+        let config = config.unwrap().with_environment(self.env.as_deref())?;
+        let (_, mut deployments) = if DeploymentResult::exists()? {
+            DeploymentResult::parse()?
+        } else {
+            (PathBuf::new(), DeploymentResult::default())
+        };
+        let export = DeploymentExport::new(&config, deployments.network(&config.deployment_key()));
+        match &self.out {
+            Some(out) => {
+                let path = export.write(self.format, &project_root, out)?;
+                println!(
+                    "Exported {} contracts to {}",
+                    export.contracts.len(),
+                    path.display().bright_blue()
+                );
+            }
+            None => print!("{}", export.render(self.format)),
+        }
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@ use crate::{
     chains::chain_profile::ChainProfile, error::WarpError, executable::Executable,
     utils::project_config::ProjectConfig,
 };
-use clap::{Args, ValueEnum};
+use clap::Args;
 use owo_colors::OwoColorize;
 use std::path::PathBuf;
 
@@ -13,14 +13,13 @@ impl Executable for FrontendCommand {
     fn execute(
         &self,
         project_root: Option<PathBuf>,
-        config: Option<ProjectConfig>,
-        profile: &Box<dyn ChainProfile>,
+        _config: Option<ProjectConfig>,
+        profile: &dyn ChainProfile,
     ) -> Result<(), WarpError> {
         if project_root.is_none() {
             return Err(WarpError::ProjectFileNotFound);
         };
         let project_root = project_root.unwrap();
-        let config = config.unwrap();
 
         println!(
             "{} {}",
@@ -0,0 +1,176 @@
+use std::{io::Write, path::PathBuf};
+
+use clap::{Args, Subcommand};
+use owo_colors::OwoColorize;
+
+use crate::{
+    chains::chain_profile::ChainProfile, error::WarpError, executable::Executable,
+    utils::keystore::KeyStore, utils::project_config::ProjectConfig,
+};
+
+#[derive(Args)]
+pub struct KeysCommand {
+    /// Keys subcommand
+    #[command(subcommand)]
+    subcommand: KeysSubcommand,
+    /// Chain profile used to derive addresses (defaults to the workspace profile)
+    #[arg(short, long, global = true)]
+    chain: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum KeysSubcommand {
+    /// Generate a new mnemonic and store it under the given name
+    Add(KeyNameArgs),
+    /// Import an existing mnemonic
+    Import(KeyNameArgs),
+    /// List all keys in the Warp key store
+    List,
+    /// Show the address of a key for the selected chain profile
+    Show(KeyNameArgs),
+    /// Remove a key from the Warp key store
+    Delete(KeyDeleteArgs),
+    /// Print the mnemonic of a key
+    Export(KeyNameArgs),
+}
+
+#[derive(Args)]
+struct KeyNameArgs {
+    /// Name of the key
+    name: String,
+}
+
+#[derive(Args)]
+struct KeyDeleteArgs {
+    /// Name of the key
+    name: String,
+    /// Skip the confirmation prompt
+    #[arg(short, long)]
+    yes: bool,
+}
+
+impl Executable for KeysCommand {
+    fn execute(
+        &self,
+        project_root: Option<PathBuf>,
+        _config: Option<ProjectConfig>,
+        profile: &dyn ChainProfile,
+    ) -> Result<(), WarpError> {
+        let custom_profile = match &self.chain {
+            Some(chain) => Some(crate::chains::load_profile(chain, project_root.as_deref())?),
+            None => None,
+        };
+        let profile = custom_profile.as_deref().unwrap_or(profile);
+        let hd_path = profile.get_hd_path();
+        let prefix = profile.get_bech32_prefix();
+        let mut keystore = KeyStore::load()?;
+
+        match &self.subcommand {
+            KeysSubcommand::Add(x) => {
+                if keystore.contains(&x.name) {
+                    return Err(WarpError::KeyAlreadyExists(x.name.clone()));
+                }
+                let password = Self::prompt_new_password()?;
+                let mnemonic = keystore.generate(&x.name, &password)?;
+                let signer = keystore.signer(&x.name, &password, &hd_path, &prefix)?;
+                keystore.save()?;
+                println!(
+                    " {} {}: {}",
+                    "=>".bright_yellow(),
+                    x.name.bright_blue(),
+                    signer.address.bright_green()
+                );
+                println!(
+                    "{} {}",
+                    "Write down this mnemonic, it is the only way to recover the key:"
+                        .bright_yellow(),
+                    mnemonic
+                );
+            }
+            KeysSubcommand::Import(x) => {
+                if keystore.contains(&x.name) {
+                    return Err(WarpError::KeyAlreadyExists(x.name.clone()));
+                }
+                let mnemonic = rpassword::prompt_password("Enter the mnemonic to import:")?;
+                let password = Self::prompt_new_password()?;
+                keystore.insert(&x.name, &mnemonic, &password)?;
+                let signer = keystore.signer(&x.name, &password, &hd_path, &prefix)?;
+                keystore.save()?;
+                println!(
+                    " {} {}: {}",
+                    "=>".bright_yellow(),
+                    x.name.bright_blue(),
+                    signer.address.bright_green()
+                );
+            }
+            KeysSubcommand::List => {
+                for name in keystore.keys.keys() {
+                    let address = keystore
+                        .cached_address(name, &hd_path, &prefix)?
+                        .unwrap_or_else(|| format!("(run 'warp keys show {}')", name));
+                    println!(
+                        " {} {}: {}",
+                        "=>".bright_yellow(),
+                        name.bright_blue(),
+                        address.bright_green()
+                    );
+                }
+            }
+            KeysSubcommand::Show(x) => {
+                let address = match keystore.cached_address(&x.name, &hd_path, &prefix)? {
+                    Some(address) => address,
+                    None => {
+                        let password = rpassword::prompt_password(format!(
+                            "Enter the password for key '{}':",
+                            x.name
+                        ))?;
+                        let signer = keystore.signer(&x.name, &password, &hd_path, &prefix)?;
+                        keystore.save()?;
+                        signer.address
+                    }
+                };
+                println!("{}", address);
+            }
+            KeysSubcommand::Delete(x) => {
+                if !keystore.contains(&x.name) {
+                    return Err(WarpError::KeyNotFound(x.name.clone()));
+                }
+                if !x.yes && !Self::confirm(&format!("Delete key '{}'?", x.name))? {
+                    return Ok(());
+                }
+                keystore.remove(&x.name)?;
+                keystore.save()?;
+                println!("{} {}", "Deleted".bright_green(), x.name.bright_blue());
+            }
+            KeysSubcommand::Export(x) => {
+                let password = rpassword::prompt_password(format!(
+                    "Enter the password for key '{}':",
+                    x.name
+                ))?;
+                println!("{}", keystore.mnemonic(&x.name, &password)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl KeysCommand {
+    fn prompt_new_password() -> Result<String, WarpError> {
+        loop {
+            let password = rpassword::prompt_password("Enter a password to encrypt the key:")?;
+            let repeated = rpassword::prompt_password("Repeat the password:")?;
+            if password == repeated {
+                return Ok(password);
+            }
+            println!("{}", "Passwords do not match.".bright_red());
+        }
+    }
+
+    fn confirm(question: &str) -> Result<bool, WarpError> {
+        print!("{} [y/N] ", question);
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        Ok(answer.trim().eq_ignore_ascii_case("y"))
+    }
+}
@@ -1,11 +1,18 @@
+pub mod autodeploy;
 pub mod build;
+pub mod codegen;
+pub mod config;
+pub mod deployments;
+pub mod docs;
+pub mod export;
+pub mod frontend;
 pub mod init;
+pub mod keys;
 pub mod new;
 pub mod node;
+pub mod rollback;
+pub mod schema;
 pub mod test;
-pub mod autodeploy;
-pub mod config;
 pub mod wasm;
-pub mod frontend;
 
 pub use build::*;
@@ -24,7 +24,7 @@ impl Executable for NewCommand {
         &self,
         project_root: Option<PathBuf>,
         config: Option<ProjectConfig>,
-        profile: &Box<dyn ChainProfile>,
+        profile: &dyn ChainProfile,
     ) -> Result<(), WarpError> {
         if project_root.is_none() {
             return Err(WarpError::ProjectFileNotFound);
@@ -39,9 +39,13 @@ impl Executable for NewCommand {
             contract: format!("artifacts/{}.wasm", &self.name),
             label: self.label.as_ref().unwrap_or(&self.name).to_string(),
             store_only: false,
-            init_msg: "{ \"owner\": \"$account_id\", \"message\": \"\" }".to_owned(),
-            migrate_msg: Some("{}".to_owned()),
+            admin: None,
+            init_msg: "{ \"owner\": \"$account_id\", \"message\": \"\" }".into(),
+            migrate_msg: Some("{}".into()),
+            rollback_msg: None,
             coins: None,
+            fees: None,
+            after: vec![],
         };
         config.autodeploy.steps.push(deploy_step);
         profile.new_contract(&contract_name, &contract_dir, &project_root)?;
@@ -62,7 +66,7 @@ mod tests {
 
     #[test]
     fn path_test() {
-        let paths = vec![
+        let paths = [
             ("path-to-file", "path_to_file"),
             ("path to file 2", "path_to_file_2"),
             (r"path/to\file", "path_to_file"),
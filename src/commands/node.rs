@@ -21,7 +21,7 @@ impl Executable for NodeCommand {
         &self,
         project_root: Option<PathBuf>,
         config: Option<ProjectConfig>,
-        profile: &Box<dyn ChainProfile>,
+        profile: &dyn ChainProfile,
     ) -> Result<(), WarpError> {
         if project_root.is_none() {
             return Err(WarpError::ProjectFileNotFound);
@@ -30,8 +30,8 @@ impl Executable for NodeCommand {
 
         let cmd_str = profile.get_node_docker_command(self.container.clone(), &config);
         let cmd_tokens = cmd_str.split(" ").collect::<Vec<&str>>();
-        let cmd_name = *cmd_tokens.get(0).unwrap();
-        let mut cmd_args = cmd_tokens.iter().skip(1).map(|x| *x).collect::<Vec<&str>>();
+        let cmd_name = *cmd_tokens.first().unwrap();
+        let mut cmd_args = cmd_tokens.iter().skip(1).copied().collect::<Vec<&str>>();
 
         let mut cmd = Command::new(cmd_name);
         if !self.persistant {
@@ -0,0 +1,151 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::{
+    chains::chain_profile::ChainProfile,
+    consts::WARP_VERSION,
+    error::WarpError,
+    executable::Executable,
+    utils::{
+        contract_schema::{ContractSchema, MessageKind},
+        deployment_result::{timestamp, DeploymentResult, MigrationRecord},
+        project_config::ProjectConfig,
+        template::TemplateContext,
+    },
+};
+
+#[derive(Args)]
+pub struct RollbackCommand {
+    /// Contract ID (from the Warp.toml file)
+    pub id: String,
+    /// Code ID to migrate to, or '~N' to go N versions back
+    #[arg(long, default_value = "~1")]
+    pub to: String,
+    /// Migrate message, overriding 'rollback_msg' of the autodeploy step
+    #[arg(long)]
+    pub msg: Option<String>,
+    /// Named environment from Warp.toml to work with
+    #[arg(long)]
+    pub env: Option<String>,
+    /// Don't check the migrate message against the contract's JSON schema
+    #[arg(long)]
+    pub skip_schema_check: bool,
+}
+
+impl Executable for RollbackCommand {
+    fn execute(
+        &self,
+        project_root: Option<PathBuf>,
+        config: Option<ProjectConfig>,
+        profile: &dyn ChainProfile,
+    ) -> Result<(), WarpError> {
+        let project_root = project_root.ok_or(WarpError::ProjectFileNotFound)?;
+        let config = config.unwrap().with_environment(self.env.as_deref())?;
+        let step = config
+            .autodeploy
+            .steps
+            .iter()
+            .find(|x| x.id == self.id)
+            .ok_or_else(|| WarpError::ContractIdNotFound(self.id.clone()))?;
+        if config.autodeploy.account_id.is_empty() {
+            return Err(WarpError::UnspecifiedWallet);
+        }
+
+        let (_, mut deployments) = DeploymentResult::parse()?;
+        let current_network = deployments.network(&config.deployment_key());
+        let record = current_network
+            .get(&self.id)
+            .ok_or_else(|| WarpError::ContractIdNotFound(self.id.clone()))?;
+        let code_id = match self.to.strip_prefix('~') {
+            Some(versions) => {
+                let versions: usize = versions
+                    .parse()
+                    .ok()
+                    .filter(|x| *x > 0)
+                    .ok_or_else(|| WarpError::InvalidRollbackTarget(self.to.clone()))?;
+                record
+                    .previous_code_id(versions)
+                    .ok_or_else(|| WarpError::NoRollbackTarget(self.id.clone()))?
+                    .to_owned()
+            }
+            None if self.to.parse::<u64>().is_ok() => self.to.clone(),
+            None => return Err(WarpError::InvalidRollbackTarget(self.to.clone())),
+        };
+        if record.code_id.as_deref() == Some(code_id.as_str()) {
+            return Err(WarpError::NoRollbackTarget(self.id.clone()));
+        }
+
+        let password =
+            rpassword::prompt_password("Enter your keyring password (if using/needed):")?;
+        let password = if password.is_empty() {
+            None
+        } else {
+            Some(password.as_str())
+        };
+        let deployment_account = profile
+            .get_key_info(&config.autodeploy.account_id, password, &config)?
+            .address;
+
+        let message = match (&self.msg, &step.rollback_msg) {
+            (Some(msg), _) => msg.clone(),
+            (None, Some(msg)) => msg.to_json(&project_root)?,
+            (None, None) => step.migrate_json(&project_root)?,
+        };
+        let mut context = TemplateContext::new(Some(&deployment_account), &config)
+            .with_deployments(current_network);
+        // The contract's own code ID is the one it's rolled back to
+        if let Some(own) = context.steps.get_mut(step.reference()) {
+            own.code_id = Some(code_id.clone());
+        }
+        let message = context.render(&message)?;
+        if !self.skip_schema_check {
+            if let Some(schema) = ContractSchema::load(&project_root, &step.contract, false)? {
+                schema.validate(MessageKind::Migrate, &message, false)?;
+            }
+        }
+
+        let record = current_network.get_mut(&self.id).unwrap();
+        let on_chain = profile
+            .query_contract_info(&record.address, &config)
+            .ok()
+            .map(|x| x.contract_info.admin);
+        record.ensure_admin(&self.id, &deployment_account, on_chain)?;
+        print!(
+            " {} {} (CODE ID: {} => {})",
+            "<=".bright_yellow(),
+            self.id.bright_blue(),
+            record.code_id.as_deref().unwrap_or("?"),
+            code_id.bright_green()
+        );
+        let migrate_tx = profile.migrate_contract(
+            &record.address,
+            &code_id,
+            &config.autodeploy.account_id,
+            &message,
+            password,
+            &config.for_step(step),
+        )?;
+        profile.query_tx(&migrate_tx.txhash, &config)?;
+        println!(
+            "\t{} ({}) -- '{}'",
+            "Done.".bright_green(),
+            &migrate_tx.txhash.bright_blue(),
+            &message.bright_yellow()
+        );
+        let checksum = record.checksum_of(&code_id);
+        record.migrated(MigrationRecord {
+            from_code_id: record.code_id.clone(),
+            code_id,
+            checksum,
+            store_tx: None,
+            migrate_tx: migrate_tx.txhash,
+            deployer: Some(deployment_account),
+            migrated_at: timestamp(),
+            warp_version: WARP_VERSION.to_owned(),
+            rollback: true,
+        });
+        deployments.save()
+    }
+}
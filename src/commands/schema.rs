@@ -0,0 +1,207 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Subcommand};
+use owo_colors::OwoColorize;
+
+use crate::{
+    chains::chain_profile::ChainProfile,
+    error::WarpError,
+    executable::Executable,
+    utils::{
+        contract_schema::ContractSchema,
+        project_config::ProjectConfig,
+        rust_codegen,
+        schema_diff::{self, Baseline, Compatibility, DEPLOYMENT_BASELINE},
+        schema_index::SchemaIndex,
+    },
+};
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct SchemaCommand {
+    /// Schema subcommand
+    #[command(subcommand)]
+    subcommand: Option<SchemaSubcommand>,
+    /// Contracts to generate schemas for (directory names under 'contracts/'), all by default
+    pub contracts: Vec<String>,
+}
+
+#[derive(Subcommand)]
+enum SchemaSubcommand {
+    /// Compare the contracts' schemas with a previous version and report breaking changes
+    Diff(SchemaDiffArgs),
+}
+
+#[derive(Args)]
+struct SchemaDiffArgs {
+    /// Contracts to compare (directory names under 'contracts/'), all by default
+    contracts: Vec<String>,
+    /// Git revision to compare with, or 'deployment' for the schemas of the last 'warp deploy'
+    #[arg(long, default_value = "HEAD")]
+    against: String,
+    /// Named environment from Warp.toml, for '--against deployment'
+    #[arg(long)]
+    env: Option<String>,
+}
+
+impl Executable for SchemaCommand {
+    fn execute(
+        &self,
+        project_root: Option<PathBuf>,
+        config: Option<ProjectConfig>,
+        _profile: &dyn ChainProfile,
+    ) -> Result<(), WarpError> {
+        let project_root = project_root.ok_or(WarpError::ProjectFileNotFound)?;
+        match &self.subcommand {
+            Some(SchemaSubcommand::Diff(x)) => x.diff(&project_root, config.unwrap()),
+            None => self.collect(&project_root),
+        }
+    }
+}
+
+/// Contract directories under `contracts/` and their names, checking that every
+/// requested contract exists
+fn contracts(
+    project_root: &Path,
+    requested: &[String],
+) -> Result<(Vec<PathBuf>, Vec<String>), WarpError> {
+    let contract_dirs = ContractSchema::contract_dirs(project_root)?;
+    let names: Vec<String> = contract_dirs
+        .iter()
+        .map(|x| {
+            x.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    if let Some(unknown) = requested.iter().find(|x| !names.contains(x)) {
+        return Err(WarpError::ContractIdNotFound(unknown.clone()));
+    }
+    Ok((contract_dirs, names))
+}
+
+impl SchemaCommand {
+    /// Runs the schema generators and collects their output into the `schema/` tree
+    fn collect(&self, project_root: &Path) -> Result<(), WarpError> {
+        let (contract_dirs, names) = contracts(project_root, &self.contracts)?;
+
+        // A partial run only refreshes the given contracts; a full one rebuilds the index
+        let previous = SchemaIndex::load(project_root)?;
+        let mut index = match self.contracts.is_empty() {
+            true => SchemaIndex::default(),
+            false => previous.clone(),
+        };
+        for name in previous.contracts.keys() {
+            if !names.contains(name) {
+                index.remove(project_root, name)?;
+            }
+        }
+        for (contract_dir, name) in contract_dirs.iter().zip(names) {
+            if !self.contracts.is_empty() && !self.contracts.contains(&name) {
+                continue;
+            }
+            index.missing_generator.retain(|x| x != &name);
+            print!(" {} {}", "=>".bright_yellow(), name.bright_blue());
+            let generated = ContractSchema::generator(contract_dir).is_some();
+            if !generated {
+                index.missing_generator.push(name.clone());
+            }
+            let Some(schema) = ContractSchema::load_dir(contract_dir, generated)? else {
+                index.remove(project_root, &name)?;
+                println!("\t{}", "No schema generator.".bright_yellow());
+                continue;
+            };
+            index.collect(project_root, &name, &schema, generated)?;
+            let status = match generated {
+                true => "Done.".bright_green().to_string(),
+                false => "No schema generator, existing schemas copied."
+                    .bright_yellow()
+                    .to_string(),
+            };
+            match rust_codegen::write(project_root, &name, &schema)? {
+                Some(interface) => {
+                    println!("\t{} (interface: {})", status, interface.display())
+                }
+                None => println!("\t{}", status),
+            }
+        }
+        index.missing_generator.sort();
+        let path = index.save(project_root)?;
+
+        println!(
+            "Collected the schemas of {} contracts into {}",
+            index.contracts.len(),
+            path.display().bright_blue()
+        );
+        if !index.missing_generator.is_empty() {
+            println!(
+                "{} {}",
+                "Contracts without a schema generator:".bright_yellow(),
+                index.missing_generator.join(", ")
+            );
+        }
+        Ok(())
+    }
+}
+
+impl SchemaDiffArgs {
+    fn diff(&self, project_root: &Path, config: ProjectConfig) -> Result<(), WarpError> {
+        let baseline = match self.against.as_str() {
+            DEPLOYMENT_BASELINE => {
+                let config = config.with_environment(self.env.as_deref())?;
+                Baseline::deployment(project_root, &config.deployment_key())
+            }
+            revision => Baseline::git(revision),
+        };
+        // The baseline is removed even if comparing fails part way
+        let breaking = self.compare(project_root, &baseline);
+        let cleanup = baseline.cleanup();
+        let breaking = breaking?;
+        cleanup?;
+        if breaking > 0 {
+            return Err(WarpError::BreakingSchemaChanges(breaking));
+        }
+        println!("{}", "No breaking schema changes.".bright_green());
+        Ok(())
+    }
+
+    /// Prints the changes of every contract since `baseline`, returning how many are breaking
+    fn compare(&self, project_root: &Path, baseline: &Baseline) -> Result<usize, WarpError> {
+        let (contract_dirs, names) = contracts(project_root, &self.contracts)?;
+        let mut breaking = 0;
+        for (contract_dir, name) in contract_dirs.iter().zip(names) {
+            if !self.contracts.is_empty() && !self.contracts.contains(&name) {
+                continue;
+            }
+            print!(" {} {}", "=>".bright_yellow(), name.bright_blue());
+            let regenerate = ContractSchema::generator(contract_dir).is_some();
+            let Some(current) = ContractSchema::load_dir(contract_dir, regenerate)? else {
+                println!("\t{}", "No schema, skipped.".bright_yellow());
+                continue;
+            };
+            let Some(previous) = baseline.load(project_root, &name)? else {
+                println!("\t{}", "Not in the baseline, skipped.".bright_yellow());
+                continue;
+            };
+            let changes = schema_diff::diff_contract(&previous, &current)?;
+            if changes.is_empty() {
+                println!("\t{}", "No changes.".bright_green());
+                continue;
+            }
+            println!();
+            for change in changes.iter() {
+                match change.compatibility {
+                    Compatibility::Breaking => {
+                        breaking += 1;
+                        println!("    {} {}", "breaking  ".bright_red(), change)
+                    }
+                    Compatibility::Compatible => {
+                        println!("    {} {}", "compatible".bright_green(), change)
+                    }
+                }
+            }
+        }
+        Ok(breaking)
+    }
+}
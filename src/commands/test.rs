@@ -24,7 +24,7 @@ impl Executable for TestCommand {
         &self,
         project_root: Option<PathBuf>,
         config: Option<ProjectConfig>,
-        profile: &Box<dyn ChainProfile>,
+        profile: &dyn ChainProfile,
     ) -> Result<(), WarpError> {
         if project_root.is_none() {
             return Err(WarpError::ProjectFileNotFound);
@@ -39,8 +39,7 @@ impl Executable for TestCommand {
         }
 
         // 2. Set up the node unless specified otherwise
-        let node_cleanup: bool;
-        if !self.skip_environment {
+        let node_cleanup = if !self.skip_environment {
             let cmd = NodeCommand {
                 detached: true,
                 container: Some(config.tests.test_container_name.clone()),
@@ -71,10 +70,10 @@ impl Executable for TestCommand {
             }
             println!("Waiting for the node to start producing blocks...");
             std::thread::sleep(Duration::from_secs(config.tests.node_setup_time as u64));
-            node_cleanup = true;
+            true
         } else {
-            node_cleanup = false;
-        }
+            false
+        };
         let mut test = Command::new("yarn")
             .arg("run")
             .arg("ts-mocha")
@@ -110,7 +109,7 @@ impl TestCommand {
             .spawn()?;
         process.wait()?;
 
-        if config.tests.persist_image == false {
+        if !config.tests.persist_image {
             let logs = Command::new("docker")
                 .args(vec![
                     "container",
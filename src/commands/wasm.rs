@@ -1,13 +1,18 @@
 use std::path::PathBuf;
 
+pub use clap::Args;
 use clap::Subcommand;
-pub use clap::{arg, Args};
 
 use crate::{
     chains::chain_profile::ChainProfile,
     error::WarpError,
     executable::Executable,
-    utils::{deployment_result::DeploymentResult, project_config::ProjectConfig},
+    utils::{
+        contract_schema::{ContractSchema, MessageKind},
+        deployment_result::DeploymentResult,
+        project_config::ProjectConfig,
+        template::TemplateContext,
+    },
 };
 
 #[derive(Args)]
@@ -15,6 +20,12 @@ pub struct WasmCommand {
     /// Wasm Subcommand
     #[command(subcommand)]
     subcommand: WasmSubcommand,
+    /// Named environment from Warp.toml to work with
+    #[arg(long, global = true)]
+    pub env: Option<String>,
+    /// Don't check the arguments against the contract's JSON schema
+    #[arg(long, global = true)]
+    pub skip_schema_check: bool,
 }
 
 #[derive(Subcommand)]
@@ -23,6 +34,12 @@ pub enum WasmSubcommand {
     Execute(WasmExecuteArgs),
     ///Query a contract in the workspace
     Query(WasmQueryArgs),
+    /// Show the on-chain info (code ID, admin, label) of a contract in the workspace
+    Info(WasmInfoArgs),
+    /// Make another address the admin of a contract in the workspace
+    SetAdmin(WasmSetAdminArgs),
+    /// Remove the admin of a contract in the workspace, so it can't be migrated anymore
+    ClearAdmin(WasmClearAdminArgs),
 }
 
 #[derive(Args, Clone)]
@@ -51,63 +68,160 @@ pub struct WasmQueryArgs {
     pub arguments: String,
 }
 
+#[derive(Args, Clone)]
+pub struct WasmInfoArgs {
+    /// Contract ID (from the Warp.toml file)
+    #[arg(required = true)]
+    pub contract: String,
+}
+
+#[derive(Args, Clone)]
+pub struct WasmSetAdminArgs {
+    /// Contract ID (from the Warp.toml file)
+    #[arg(required = true)]
+    pub contract: String,
+    /// Address of the new admin, or a '$name' placeholder of another contract
+    #[arg(required = true)]
+    pub admin: String,
+    /// Current admin account, defaults to the deployment account
+    #[arg(long, short)]
+    pub from: Option<String>,
+}
+
+#[derive(Args, Clone)]
+pub struct WasmClearAdminArgs {
+    /// Contract ID (from the Warp.toml file)
+    #[arg(required = true)]
+    pub contract: String,
+    /// Current admin account, defaults to the deployment account
+    #[arg(long, short)]
+    pub from: Option<String>,
+}
+
 impl Executable for WasmCommand {
     fn execute(
         &self,
         project_root: Option<PathBuf>,
         config: Option<ProjectConfig>,
-        profile: &Box<dyn ChainProfile>,
+        profile: &dyn ChainProfile,
     ) -> Result<(), crate::error::WarpError> {
         DeploymentResult::exists()?;
         if project_root.is_none() {
             return Err(WarpError::ProjectFileNotFound);
         };
         //let project_root = project_root.unwrap();
-        let config = config.unwrap();
+        let config = config.unwrap().with_environment(self.env.as_deref())?;
         let (_, mut deployments) = DeploymentResult::parse()?;
 
         // Translate contract address
         let contract_id = match &self.subcommand {
             WasmSubcommand::Execute(x) => &x.contract,
             WasmSubcommand::Query(x) => &x.contract,
+            WasmSubcommand::Info(x) => &x.contract,
+            WasmSubcommand::SetAdmin(x) => &x.contract,
+            WasmSubcommand::ClearAdmin(x) => &x.contract,
         };
-        let contract_address = deployments
-            .network(&config.network.chain_id)
-            .get(contract_id);
+        let records = deployments.network(&config.deployment_key());
+        let contract_address = records.get(contract_id).map(|x| x.address.as_str());
         if contract_address.is_none() {
             return Err(WarpError::ContractIdNotFound(contract_id.to_owned()));
         }
-        let contract_address = contract_address.unwrap();
+        let contract_address = contract_address.unwrap().to_owned();
+        let contract_address = contract_address.as_str();
 
         match &self.subcommand {
             WasmSubcommand::Execute(x) => {
-                let from = x.from.as_ref().unwrap_or(&config.autodeploy.account_id);
-                if from.is_empty() {
-                    return Err(WarpError::UnspecifiedWallet);
-                }
-
-                let password =
-                    rpassword::prompt_password("Enter your keyring password (if using/needed):")?;
-                let password = if password.is_empty() {
-                    None
-                } else {
-                    Some(password.as_str())
-                };
-
-                profile.execute_contract(
-                    &contract_address,
-                    &x.arguments,
+                let (from, password) = Self::sender(x.from.as_ref(), &config)?;
+                let password = password.as_deref();
+                let sender = profile.get_key_info(from, password, &config)?.address;
+                let arguments = TemplateContext::new(Some(&sender), &config)
+                    .with_deployments(records)
+                    .render(&x.arguments)?;
+                self.validate_arguments(&config, contract_id, MessageKind::Execute, &arguments)?;
+                let tx = profile.execute_contract(
+                    contract_address,
+                    &arguments,
                     from,
                     password,
                     &config,
                 )?;
+                profile.query_tx(&tx.txhash, &config)?;
             }
             WasmSubcommand::Query(x) => {
-                let result =
-                    profile.query_contract_smart(contract_address, &x.arguments, &config)?;
+                let arguments = TemplateContext::new(None, &config)
+                    .with_deployments(records)
+                    .render(&x.arguments)?;
+                self.validate_arguments(&config, contract_id, MessageKind::Query, &arguments)?;
+                let result = profile.query_contract_smart(contract_address, &arguments, &config)?;
                 println!("{result}");
             }
+            WasmSubcommand::Info(_) => {
+                let result = profile.query_contract_info(contract_address, &config)?;
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            }
+            WasmSubcommand::SetAdmin(x) => {
+                let admin = TemplateContext::new(None, &config)
+                    .with_deployments(records)
+                    .render(&serde_json::Value::from(x.admin.as_str()).to_string())?;
+                let admin: String = serde_json::from_str(&admin)?;
+                let (from, password) = Self::sender(x.from.as_ref(), &config)?;
+                let tx = profile.update_admin(
+                    contract_address,
+                    &admin,
+                    from,
+                    password.as_deref(),
+                    &config,
+                )?;
+                profile.query_tx(&tx.txhash, &config)?;
+                println!("Admin of {} set to {} ({})", contract_id, admin, tx.txhash);
+                records.get_mut(contract_id).unwrap().admin = Some(admin);
+                deployments.save()?;
+            }
+            WasmSubcommand::ClearAdmin(x) => {
+                let (from, password) = Self::sender(x.from.as_ref(), &config)?;
+                let tx =
+                    profile.clear_admin(contract_address, from, password.as_deref(), &config)?;
+                profile.query_tx(&tx.txhash, &config)?;
+                println!("Admin of {} cleared ({})", contract_id, tx.txhash);
+                records.get_mut(contract_id).unwrap().admin = None;
+                deployments.save()?;
+            }
         }
         Ok(())
     }
 }
+
+impl WasmCommand {
+    /// Signing account, falling back to the deployment account, and its keyring password
+    fn sender<'a>(
+        from: Option<&'a String>,
+        config: &'a ProjectConfig,
+    ) -> Result<(&'a str, Option<String>), WarpError> {
+        let from = from.unwrap_or(&config.autodeploy.account_id);
+        if from.is_empty() {
+            return Err(WarpError::UnspecifiedWallet);
+        }
+        let password =
+            rpassword::prompt_password("Enter your keyring password (if using/needed):")?;
+        Ok((from, Some(password).filter(|x| !x.is_empty())))
+    }
+
+    /// Checks the arguments against the schema of the contract behind the autodeploy step
+    fn validate_arguments(
+        &self,
+        config: &ProjectConfig,
+        contract_id: &str,
+        kind: MessageKind,
+        arguments: &str,
+    ) -> Result<(), WarpError> {
+        let step = config.autodeploy.steps.iter().find(|x| x.id == contract_id);
+        let (Some(step), false) = (step, self.skip_schema_check) else {
+            return Ok(());
+        };
+        let project_root = ProjectConfig::find_project_root()?;
+        match ContractSchema::load(&project_root, &step.contract, false)? {
+            Some(schema) => schema.validate(kind, arguments, false),
+            None => Ok(()),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+pub const WARP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 pub const MSG_FILE: &str = "use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::Addr;
 
@@ -10,7 +10,9 @@ pub enum WarpError {
     TomlSerializationError(#[from] toml::ser::Error),
     #[error("Toml Deserialization Error: {0:?}")]
     TomlDeserializationError(#[from] toml::de::Error),
-    #[error("Project file can't be found. You have to navigate to a valid Warp project directory.")]
+    #[error(
+        "Project file can't be found. You have to navigate to a valid Warp project directory."
+    )]
     ProjectFileNotFound,
     #[error("Another Warp project already exists at '{0}'.")]
     ProjectFileAlreadyExists(PathBuf),
@@ -34,4 +36,84 @@ pub enum WarpError {
     UnderlyingCliError(String),
     #[error("Can't match the following ID: '{0}'")]
     ContractIdNotFound(String),
+    #[error("Contract '{0}' has no schema, add a schema generator or a 'schema/' directory")]
+    ContractSchemaNotFound(String),
+    #[error("Unknown chain profile '{0}'. Add a chain definition to '.warp/chains/{0}.toml' in the project or to your Warp config directory.")]
+    UnknownChainProfile(String),
+    #[error("The '{0}' profile does not define a '{1}' network.")]
+    NetworkNotSupported(String, String),
+    #[error("The '{0}' profile does not support frontend initialization (yet).")]
+    FrontendNotSupported(String),
+    #[error("LCD endpoint returned nothing for '{0}'")]
+    LcdNotFound(String),
+    #[error("LCD request failed with status {0}: {1}")]
+    LcdError(u16, String),
+    #[error("LCD request failed: {0}")]
+    LcdTransportError(String),
+    #[error("Native signing requires 'lcd_url' to be set in the [network] section of Warp.toml")]
+    LcdRequired,
+    #[error("The '{0}' profile does not support native transaction signing.")]
+    NativeTxNotSupported(String),
+    #[error("No signing key available for '{0}'. Add it with 'warp keys add/import' or set the WARP_MNEMONIC environment variable.")]
+    MissingSigningKey(String),
+    #[error("Could not determine the user config directory")]
+    ConfigDirNotFound,
+    #[error("Key '{0}' already exists")]
+    KeyAlreadyExists(String),
+    #[error("Key '{0}' not found in the Warp key store")]
+    KeyNotFound(String),
+    #[error("Could not decrypt key '{0}' (wrong password?)")]
+    KeyDecryptionFailed(String),
+    #[error("Invalid key: {0}")]
+    InvalidKey(String),
+    #[error("Invalid coins: '{0}'")]
+    InvalidCoins(String),
+    #[error("Invalid code ID: '{0}'")]
+    InvalidCodeId(String),
+    #[error("An unfinished deployment was found. Run 'warp deploy --resume' to continue it, or delete '{0}' to start over")]
+    UnfinishedDeployment(PathBuf),
+    #[error("There is no unfinished deployment to resume")]
+    NothingToResume,
+    #[error("The unfinished deployment targets '{0}', but the workspace is configured for '{1}'")]
+    JournalNetworkMismatch(String, String),
+    #[error("Two autodeploy steps share the id '{0}'")]
+    DuplicateStepId(String),
+    #[error("Autodeploy step '{0}' references '{1}', which is not the id of any step")]
+    UnknownStepReference(String, String),
+    #[error("Autodeploy step '{0}' references the address '{1}' of a store-only step")]
+    StoreOnlyReference(String, String),
+    #[error("Autodeploy steps reference each other's addresses in a cycle: {0}")]
+    DeploymentCycle(String),
+    #[error("The 'fixed' gas strategy requires 'gas_limit' to be set")]
+    MissingGasLimit,
+    #[error("Could not estimate gas prices and no static price is configured, set 'gas_prices' for the network")]
+    GasPriceUnavailable,
+    #[error("Transaction {0} was not included in a block within {1}s")]
+    TxTimeout(String, u64),
+    #[error("Invalid template expression '{0}': {1}")]
+    TemplateError(String, String),
+    #[error("The message is not valid JSON ({1}): {0}")]
+    InvalidMessage(String, String),
+    #[error("The message does not match the contract's {0} schema:\n{1}")]
+    SchemaViolation(String, String),
+    #[error("The schema generator of '{0}' failed: {1}")]
+    SchemaGenerationFailed(PathBuf, String),
+    #[error("Warp.toml does not define the environment '{0}'")]
+    UnknownEnvironment(String),
+    #[error("'{0}' is administered by {1}, so the deployer {2} can't migrate it. Run 'warp wasm set-admin' from the admin account first")]
+    NotContractAdmin(String, String, String),
+    #[error("'{0}' has no admin, so it can't be migrated")]
+    ImmutableContract(String),
+    #[error("The admin of autodeploy step '{0}' is not an address: {1}")]
+    InvalidAdmin(String, String),
+    #[error("'{0}' has no earlier code ID to roll back to")]
+    NoRollbackTarget(String),
+    #[error("Invalid rollback target '{0}'. Use a code ID or '~N' to go N versions back")]
+    InvalidRollbackTarget(String),
+    #[error("Transaction {1} did not emit the '{0}' event attribute")]
+    TxEventNotFound(String, String),
+    #[error("'git {0}' failed: {1}")]
+    GitFailed(String, String),
+    #[error("Found {0} breaking schema changes")]
+    BreakingSchemaChanges(usize),
 }
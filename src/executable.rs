@@ -7,6 +7,6 @@ pub trait Executable {
         &self,
         project_root: Option<std::path::PathBuf>,
         config: Option<ProjectConfig>,
-        profile: &Box<dyn ChainProfile>,
+        profile: &dyn ChainProfile,
     ) -> Result<(), WarpError>;
 }
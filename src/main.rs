@@ -6,11 +6,12 @@ mod error;
 mod executable;
 mod utils;
 
-use chains::{archway::ArchwayProfile, chain_profile::ChainProfile};
-use clap::{command, Parser, Subcommand};
+use clap::{Parser, Subcommand};
 use commands::{
-    autodeploy::AutoDeployCommand, build::BuildCommand, config::ConfigCommand,
-    frontend::FrontendCommand, init::InitCommand, new::NewCommand, node::NodeCommand,
+    autodeploy::AutoDeployCommand, build::BuildCommand, codegen::CodegenCommand,
+    config::ConfigCommand, deployments::DeploymentsCommand, docs::DocsCommand,
+    export::ExportCommand, frontend::FrontendCommand, init::InitCommand, keys::KeysCommand,
+    new::NewCommand, node::NodeCommand, rollback::RollbackCommand, schema::SchemaCommand,
     test::TestCommand, wasm::WasmCommand,
 };
 use error::WarpError;
@@ -33,14 +34,28 @@ enum Commands {
     Config(ConfigCommand),
     /// Build the current workspace
     Build(BuildCommand),
+    /// Generate client code from the contracts' JSON schemas
+    Codegen(CodegenCommand),
     /// Execute the 'Auto Deploy' script for the workspace (see Warp.toml)
     Deploy(AutoDeployCommand),
+    /// Browse the deployment records in Deployment.toml
+    Deployments(DeploymentsCommand),
+    /// Generate Markdown reference pages from the contracts' JSON schemas
+    Docs(DocsCommand),
+    /// Write the deployed contract addresses for frontends and scripts
+    Export(ExportCommand),
     /// Initialize the frontend for the current workspace
     Frontend(FrontendCommand),
+    /// Manage the keys stored by Warp
+    Keys(KeysCommand),
     /// Scaffold a new contract
     New(NewCommand),
     /// [WIP] Start the local validator node
     Node(NodeCommand),
+    /// Migrate a deployed contract back to an earlier code ID
+    Rollback(RollbackCommand),
+    /// Run the contracts' schema generators, collect the schemas into 'schema/' and compare versions
+    Schema(SchemaCommand),
     /// Run the JavaScript tests from the '/tests/' directory
     Test(TestCommand),
     /// Wasm commands for interacting with deployed contracts
@@ -52,19 +67,16 @@ fn main() -> Result<(), WarpError> {
 
     let (project_root, config) = utils::project_config::ProjectConfig::parse_project_config()
         .map_or((None, None), |x| (Some(x.0), Some(x.1)));
-    let profile = if config.is_some() {
-        Some(match config.as_ref().unwrap().network.profile.as_str() {
-            "archway" => Box::new(ArchwayProfile) as Box<dyn ChainProfile>,
-            "xion" => Box::new(chains::xion::XionProfile) as Box<dyn ChainProfile>,
-            "scrt" => Box::new(chains::secret::SecretNetworkProfile) as Box<dyn ChainProfile>,
-            _ => panic!("Unknown profile"),
-        })
-    } else {
-        None
+    let profile = match &config {
+        Some(config) => Some(chains::load_profile(
+            &config.network.profile,
+            project_root.as_deref(),
+        )?),
+        None => None,
     };
 
     match &cli.command {
-        Commands::Init(_) => (),
+        Commands::Init(_) | Commands::Keys(_) => (),
         _ => {
             if profile.is_none() {
                 return Err(WarpError::ProjectFileNotFound);
@@ -74,23 +86,32 @@ fn main() -> Result<(), WarpError> {
 
     // You can check for the existence of subcommands, and if found use their
     // matches just as you would the top level cmd
+    let profile = match profile {
+        Some(profile) => profile,
+        None => chains::load_profile("archway", None)?,
+    };
+    let profile = profile.as_ref();
     let result = match &cli.command {
-        Commands::Deploy(x) => x.execute(project_root, config, &profile.unwrap()),
-        Commands::Init(x) => x.execute(
-            project_root,
-            config,
-            &profile.unwrap_or(Box::new(ArchwayProfile) as Box<dyn ChainProfile>),
-        ),
-        Commands::New(x) => x.execute(project_root, config, &profile.unwrap()),
-        Commands::Build(x) => x.execute(project_root, config, &profile.unwrap()),
-        Commands::Test(x) => x.execute(project_root, config, &profile.unwrap()),
-        Commands::Node(x) => x.execute(project_root, config, &profile.unwrap()),
-        Commands::Config(x) => x.execute(project_root, config, &profile.unwrap()),
-        Commands::Wasm(x) => x.execute(project_root, config, &profile.unwrap()),
-        Commands::Frontend(x) => x.execute(project_root, config, &profile.unwrap()),
+        Commands::Deploy(x) => x.execute(project_root, config, profile),
+        Commands::Deployments(x) => x.execute(project_root, config, profile),
+        Commands::Docs(x) => x.execute(project_root, config, profile),
+        Commands::Export(x) => x.execute(project_root, config, profile),
+        Commands::Init(x) => x.execute(project_root, config, profile),
+        Commands::New(x) => x.execute(project_root, config, profile),
+        Commands::Build(x) => x.execute(project_root, config, profile),
+        Commands::Test(x) => x.execute(project_root, config, profile),
+        Commands::Node(x) => x.execute(project_root, config, profile),
+        Commands::Config(x) => x.execute(project_root, config, profile),
+        Commands::Codegen(x) => x.execute(project_root, config, profile),
+        Commands::Rollback(x) => x.execute(project_root, config, profile),
+        Commands::Schema(x) => x.execute(project_root, config, profile),
+        Commands::Wasm(x) => x.execute(project_root, config, profile),
+        Commands::Frontend(x) => x.execute(project_root, config, profile),
+        Commands::Keys(x) => x.execute(project_root, config, profile),
     };
     if let Err(x) = result {
         println!("{} {}", "Error!".red(), x.to_string().bright_red());
+        std::process::exit(1);
     }
     Ok(())
 }
@@ -0,0 +1,357 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use serde_json::Value;
+
+use crate::error::WarpError;
+
+use super::json_schema;
+
+/// Message types of a contract that have a JSON schema
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageKind {
+    Instantiate,
+    Execute,
+    Query,
+    Migrate,
+}
+
+impl MessageKind {
+    pub const ALL: [MessageKind; 4] = [
+        MessageKind::Instantiate,
+        MessageKind::Execute,
+        MessageKind::Query,
+        MessageKind::Migrate,
+    ];
+
+    /// Key in the combined `write_api!` schema and name of the `schema/raw/` file
+    fn key(&self) -> &'static str {
+        match self {
+            MessageKind::Instantiate => "instantiate",
+            MessageKind::Execute => "execute",
+            MessageKind::Query => "query",
+            MessageKind::Migrate => "migrate",
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            MessageKind::Instantiate => "InstantiateMsg",
+            MessageKind::Execute => "ExecuteMsg",
+            MessageKind::Query => "QueryMsg",
+            MessageKind::Migrate => "MigrateMsg",
+        }
+    }
+}
+
+/// The `schema/` directory of a contract, as written by its schema generator
+pub struct ContractSchema {
+    dir: PathBuf,
+}
+
+impl ContractSchema {
+    /// Source directory of the contract built into `wasm`: `contracts/<name>`, where
+    /// `<name>` is the file name in the form `warp new` uses for directories
+    pub fn contract_dir(project_root: &Path, wasm: &str) -> PathBuf {
+        let stem = Path::new(wasm)
+            .file_stem()
+            .and_then(|x| x.to_str())
+            .unwrap_or_default();
+        let name: String = stem
+            .chars()
+            .map(|x| if x.is_ascii_alphanumeric() { x } else { '_' })
+            .collect();
+        project_root
+            .join("contracts")
+            .join(name.trim_matches('_').to_lowercase())
+    }
+
+    /// Schema of the contract built into `wasm`, running its schema generator first if
+    /// there is no `schema/` directory yet (or `regenerate` is set). `None` when the
+    /// contract has neither schemas nor a generator.
+    pub fn load(
+        project_root: &Path,
+        wasm: &str,
+        regenerate: bool,
+    ) -> Result<Option<Self>, WarpError> {
+        Self::load_dir(&Self::contract_dir(project_root, wasm), regenerate)
+    }
+
+    /// Like [`ContractSchema::load`], for the contract in `contract_dir`
+    pub fn load_dir(contract_dir: &Path, regenerate: bool) -> Result<Option<Self>, WarpError> {
+        let dir = contract_dir.join("schema");
+        if regenerate || !dir.exists() {
+            Self::generate(contract_dir)?;
+        }
+        Ok(dir.exists().then_some(Self { dir }))
+    }
+
+    /// `cargo run` target flag of the contract's schema generator: `--example` for
+    /// `examples/schema.rs`, `--bin` for `src/bin/schema.rs`, `None` without one
+    pub fn generator(contract_dir: &Path) -> Option<&'static str> {
+        if contract_dir.join("examples").join("schema.rs").exists() {
+            Some("--example")
+        } else if contract_dir
+            .join("src")
+            .join("bin")
+            .join("schema.rs")
+            .exists()
+        {
+            Some("--bin")
+        } else {
+            None
+        }
+    }
+
+    /// Runs the contract's schema generator, if it has one
+    fn generate(contract_dir: &Path) -> Result<(), WarpError> {
+        let Some(target) = Self::generator(contract_dir) else {
+            return Ok(());
+        };
+        let output = Command::new("cargo")
+            .args(["run", "--quiet", target, "schema"])
+            .current_dir(contract_dir)
+            .output()?;
+        if !output.status.success() {
+            return Err(WarpError::SchemaGenerationFailed(
+                contract_dir.to_path_buf(),
+                String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The `schema/` directory itself
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// JSON schema of one message type. Understands the `write_api!` layout (a combined
+    /// `<contract>.json` plus `raw/<kind>.json`) and the older `<kind>_msg.json` files.
+    pub fn message_schema(&self, kind: MessageKind) -> Result<Option<Value>, WarpError> {
+        let raw = self.dir.join("raw").join(format!("{}.json", kind.key()));
+        if raw.exists() {
+            return Ok(Some(serde_json::from_str(&fs::read_to_string(raw)?)?));
+        }
+        let legacy = self.dir.join(format!("{}_msg.json", kind.key()));
+        if legacy.exists() {
+            return Ok(Some(serde_json::from_str(&fs::read_to_string(legacy)?)?));
+        }
+        for entry in fs::read_dir(&self.dir)?.flatten() {
+            if entry.path().extension().is_some_and(|x| x == "json") {
+                let combined: Value = serde_json::from_str(&fs::read_to_string(entry.path())?)?;
+                if let Some(schema) = combined.get(kind.key()).filter(|x| x.is_object()) {
+                    return Ok(Some(schema.clone()));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Response schemas of the query variants, by variant name. Read from the combined
+    /// `write_api!` schema or the `raw/response_to_<query>.json` files.
+    pub fn responses(&self) -> Result<BTreeMap<String, Value>, WarpError> {
+        let mut responses = BTreeMap::new();
+        let raw = self.dir.join("raw");
+        if raw.exists() {
+            for entry in fs::read_dir(raw)?.flatten() {
+                let file_name = entry.file_name().to_string_lossy().into_owned();
+                let Some(query) = file_name
+                    .strip_prefix("response_to_")
+                    .and_then(|x| x.strip_suffix(".json"))
+                else {
+                    continue;
+                };
+                let schema = serde_json::from_str(&fs::read_to_string(entry.path())?)?;
+                responses.insert(query.to_owned(), schema);
+            }
+        }
+        if !responses.is_empty() {
+            return Ok(responses);
+        }
+        for entry in fs::read_dir(&self.dir)?.flatten() {
+            if entry.path().extension().is_some_and(|x| x == "json") {
+                let combined: Value = serde_json::from_str(&fs::read_to_string(entry.path())?)?;
+                if let Some(Value::Object(map)) = combined.get("responses") {
+                    responses.extend(map.iter().map(|(k, v)| (k.clone(), v.clone())));
+                }
+            }
+        }
+        Ok(responses)
+    }
+
+    /// Contract directories under `contracts/` (those with a `Cargo.toml`), sorted by name
+    pub fn contract_dirs(project_root: &Path) -> Result<Vec<PathBuf>, WarpError> {
+        let contracts = project_root.join("contracts");
+        if !contracts.exists() {
+            return Ok(vec![]);
+        }
+        let mut dirs: Vec<PathBuf> = fs::read_dir(contracts)?
+            .flatten()
+            .map(|x| x.path())
+            .filter(|x| x.join("Cargo.toml").exists())
+            .collect();
+        dirs.sort();
+        Ok(dirs)
+    }
+
+    /// Checks `msg` against the schema of its message type, if the contract has one.
+    /// `placeholders` accepts `<...>` stand-ins for values that don't exist yet.
+    pub fn validate(
+        &self,
+        kind: MessageKind,
+        msg: &str,
+        placeholders: bool,
+    ) -> Result<(), WarpError> {
+        let Some(schema) = self.message_schema(kind)? else {
+            return Ok(());
+        };
+        let violations = json_schema::validate(&schema, &serde_json::from_str(msg)?, placeholders);
+        if violations.is_empty() {
+            return Ok(());
+        }
+        let details: Vec<String> = violations.iter().map(|x| format!("  {}", x)).collect();
+        Err(WarpError::SchemaViolation(
+            kind.type_name().to_owned(),
+            details.join("\n"),
+        ))
+    }
+}
+
+/// A variant of an enum message such as `ExecuteMsg`
+#[derive(Clone, Debug, PartialEq)]
+pub struct MessageVariant {
+    /// Name on the wire, e.g. `update_config`
+    pub name: String,
+    /// Schema of the variant's fields, `None` for unit variants sent as a plain string
+    pub fields: Option<Value>,
+    pub description: Option<String>,
+}
+
+/// Variants of an enum message as `cosmwasm_schema` writes them: a `oneOf` of objects
+/// with a single property, or of string enums. Empty for messages that aren't enums.
+pub fn message_variants(schema: &Value) -> Vec<MessageVariant> {
+    let alternatives = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array);
+    let mut variants = vec![];
+    for alternative in alternatives.into_iter().flatten() {
+        let description = alternative
+            .get("description")
+            .and_then(Value::as_str)
+            .map(str::to_owned);
+        if let Some(names) = alternative.get("enum").and_then(Value::as_array) {
+            variants.extend(
+                names
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(|name| MessageVariant {
+                        name: name.to_owned(),
+                        fields: None,
+                        description: description.clone(),
+                    }),
+            );
+            continue;
+        }
+        let properties = alternative.get("properties").and_then(Value::as_object);
+        if let Some((name, fields)) = properties
+            .filter(|x| x.len() == 1)
+            .and_then(|x| x.iter().next())
+        {
+            variants.push(MessageVariant {
+                name: name.clone(),
+                fields: Some(fields.clone()),
+                description,
+            });
+        }
+    }
+    variants
+}
+
+/// The schema without the `null` alternative of an `Option<T>`, and whether it had one
+pub fn nullable(schema: &Value) -> (&Value, bool) {
+    if let Some([first, second]) = schema
+        .get("anyOf")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+    {
+        let is_null = |x: &Value| x.get("type") == Some(&Value::from("null"));
+        if is_null(second) {
+            return (first, true);
+        }
+        if is_null(first) {
+            return (second, true);
+        }
+    }
+    let nullable = schema
+        .get("type")
+        .and_then(Value::as_array)
+        .is_some_and(|x| x.contains(&Value::from("null")));
+    (schema, nullable)
+}
+
+/// Named sub-schemas (`definitions` or `$defs`) of all `schemas`, the first one of a name winning
+pub fn definitions<'a>(schemas: impl IntoIterator<Item = &'a Value>) -> BTreeMap<String, Value> {
+    let mut definitions = BTreeMap::new();
+    for schema in schemas {
+        for key in ["definitions", "$defs"] {
+            if let Some(Value::Object(map)) = schema.get(key) {
+                for (name, definition) in map.iter() {
+                    definitions
+                        .entry(name.clone())
+                        .or_insert_with(|| definition.clone());
+                }
+            }
+        }
+    }
+    definitions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_combined_and_legacy_schema_layouts() {
+        let root = std::env::temp_dir().join(format!("warp-schema-{}", std::process::id()));
+        let dir = ContractSchema::contract_dir(&root, "artifacts/my-token.wasm");
+        assert_eq!(dir, root.join("contracts").join("my_token"));
+        fs::create_dir_all(dir.join("schema")).unwrap();
+        fs::write(
+            dir.join("schema").join("my-token.json"),
+            r#"{ "contract_name": "my-token", "instantiate": { "type": "object", "required": ["owner"] }, "migrate": null }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("schema").join("query_msg.json"),
+            r#"{ "type": "string", "enum": ["config"] }"#,
+        )
+        .unwrap();
+
+        let schema = ContractSchema::load(&root, "artifacts/my-token.wasm", false)
+            .unwrap()
+            .unwrap();
+        assert!(schema
+            .validate(MessageKind::Instantiate, r#"{"owner":"a"}"#, false)
+            .is_ok());
+        assert!(matches!(
+            schema.validate(MessageKind::Instantiate, "{}", false),
+            Err(WarpError::SchemaViolation(kind, details)) if kind == "InstantiateMsg" && details.contains("'owner'")
+        ));
+        assert!(schema
+            .validate(MessageKind::Query, r#""config""#, false)
+            .is_ok());
+        assert!(schema
+            .validate(MessageKind::Query, r#""state""#, false)
+            .is_err());
+        assert!(schema
+            .validate(MessageKind::Migrate, r#"{"anything":1}"#, false)
+            .is_ok());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,248 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::WarpError;
+
+use super::{deployment_result::DeploymentRecord, project_config::ProjectConfig};
+
+/// File formats `warp export` can write
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Json,
+    /// `KEY=value` lines for `.env` files
+    Env,
+    /// TypeScript module with `as const` exports
+    Ts,
+    Yaml,
+}
+
+/// `[[autodeploy.export]]`: a file rewritten after every successful `warp deploy`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ExportTarget {
+    pub format: ExportFormat,
+    /// Path relative to the project root
+    pub out: PathBuf,
+}
+
+/// Network settings and deployed contracts of one Deployment.toml network, keyed by
+/// the step id without its sigil and leading underscores (`$_acl` becomes `acl`)
+#[derive(Clone, Debug)]
+pub struct DeploymentExport {
+    pub chain_id: String,
+    pub environment: Option<String>,
+    pub rpc_url: String,
+    pub lcd_url: Option<String>,
+    pub denom: String,
+    pub contracts: BTreeMap<String, ExportedContract>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExportedContract {
+    pub address: String,
+    pub code_id: Option<String>,
+}
+
+impl DeploymentExport {
+    pub fn new(config: &ProjectConfig, records: &BTreeMap<String, DeploymentRecord>) -> Self {
+        let contracts = records
+            .iter()
+            .map(|(id, record)| {
+                let name = id.trim_start_matches(['$', '#']).trim_start_matches('_');
+                (
+                    name.to_owned(),
+                    ExportedContract {
+                        address: record.address.clone(),
+                        code_id: record.code_id.clone(),
+                    },
+                )
+            })
+            .collect();
+        Self {
+            chain_id: config.network.chain_id.clone(),
+            environment: config.environment.clone(),
+            rpc_url: config.network.rpc_url.clone(),
+            lcd_url: config.network.lcd_url.clone().filter(|x| !x.is_empty()),
+            denom: config.network.denom.clone(),
+            contracts,
+        }
+    }
+
+    pub fn render(&self, format: ExportFormat) -> String {
+        match format {
+            ExportFormat::Json => format!("{:#}\n", self.to_json()),
+            ExportFormat::Env => self.to_env(),
+            ExportFormat::Ts => self.to_ts(),
+            ExportFormat::Yaml => self.to_yaml(),
+        }
+    }
+
+    /// Writes the export to `out`, relative paths being resolved against the project root
+    pub fn write(
+        &self,
+        format: ExportFormat,
+        project_root: &Path,
+        out: &Path,
+    ) -> Result<PathBuf, WarpError> {
+        let path = project_root.join(out);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, self.render(format))?;
+        Ok(path)
+    }
+
+    fn to_json(&self) -> Value {
+        let contracts: serde_json::Map<String, Value> = self
+            .contracts
+            .iter()
+            .map(|(name, contract)| {
+                let value = json!({
+                    "address": contract.address,
+                    "code_id": contract.code_id.as_deref().map(code_id_value),
+                });
+                (name.clone(), value)
+            })
+            .collect();
+        json!({
+            "chain_id": self.chain_id,
+            "environment": self.environment,
+            "rpc_url": self.rpc_url,
+            "lcd_url": self.lcd_url,
+            "denom": self.denom,
+            "contracts": contracts,
+        })
+    }
+
+    fn to_env(&self) -> String {
+        let mut lines = vec![
+            format!("CHAIN_ID={}", self.chain_id),
+            format!("RPC_URL={}", self.rpc_url),
+        ];
+        if let Some(lcd_url) = &self.lcd_url {
+            lines.push(format!("LCD_URL={}", lcd_url));
+        }
+        lines.push(format!("DENOM={}", self.denom));
+        for (name, contract) in self.contracts.iter() {
+            let name = env_name(name);
+            lines.push(format!("{}_ADDRESS={}", name, contract.address));
+            if let Some(code_id) = &contract.code_id {
+                lines.push(format!("{}_CODE_ID={}", name, code_id));
+            }
+        }
+        lines.join("\n") + "\n"
+    }
+
+    fn to_ts(&self) -> String {
+        let mut output = "// Generated by `warp export`, do not edit\n\n".to_owned();
+        output.push_str(&format!(
+            "export const chainId = {};\n",
+            json!(self.chain_id)
+        ));
+        output.push_str(&format!("export const rpcUrl = {};\n", json!(self.rpc_url)));
+        if let Some(lcd_url) = &self.lcd_url {
+            output.push_str(&format!("export const lcdUrl = {};\n", json!(lcd_url)));
+        }
+        output.push_str(&format!("export const denom = {};\n\n", json!(self.denom)));
+        output.push_str("export const contracts = {\n");
+        for (name, contract) in self.contracts.iter() {
+            let code_id = contract
+                .code_id
+                .as_deref()
+                .map(code_id_value)
+                .unwrap_or(Value::Null);
+            output.push_str(&format!(
+                "  {}: {{ address: {}, codeId: {} }},\n",
+                json!(name),
+                json!(contract.address),
+                code_id
+            ));
+        }
+        output.push_str("} as const;\n");
+        output
+    }
+
+    /// JSON scalars are valid YAML, so values are written with JSON quoting
+    fn to_yaml(&self) -> String {
+        let mut output = format!("chain_id: {}\n", json!(self.chain_id));
+        if let Some(environment) = &self.environment {
+            output.push_str(&format!("environment: {}\n", json!(environment)));
+        }
+        output.push_str(&format!("rpc_url: {}\n", json!(self.rpc_url)));
+        if let Some(lcd_url) = &self.lcd_url {
+            output.push_str(&format!("lcd_url: {}\n", json!(lcd_url)));
+        }
+        output.push_str(&format!("denom: {}\n", json!(self.denom)));
+        output.push_str(if self.contracts.is_empty() {
+            "contracts: {}\n"
+        } else {
+            "contracts:\n"
+        });
+        for (name, contract) in self.contracts.iter() {
+            output.push_str(&format!("  {}:\n", json!(name)));
+            output.push_str(&format!("    address: {}\n", json!(contract.address)));
+            if let Some(code_id) = &contract.code_id {
+                output.push_str(&format!("    code_id: {}\n", code_id_value(code_id)));
+            }
+        }
+        output
+    }
+}
+
+/// Code IDs as numbers where they are numeric
+fn code_id_value(code_id: &str) -> Value {
+    code_id
+        .parse::<u64>()
+        .map(Value::from)
+        .unwrap_or_else(|_| Value::from(code_id))
+}
+
+/// `my-token` becomes `MY_TOKEN`
+fn env_name(name: &str) -> String {
+    name.chars()
+        .map(|x| match x.is_ascii_alphanumeric() {
+            true => x.to_ascii_uppercase(),
+            false => '_',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_every_format() {
+        let mut config = ProjectConfig::empty();
+        config.network.chain_id = "constantine-3".to_owned();
+        config.network.denom = "aconst".to_owned();
+        let records = BTreeMap::from([(
+            "$_acl-v2".to_owned(),
+            DeploymentRecord {
+                address: "archway1acl".to_owned(),
+                code_id: Some("7".to_owned()),
+                ..Default::default()
+            },
+        )]);
+        let export = DeploymentExport::new(&config, &records);
+
+        let json: Value = serde_json::from_str(&export.render(ExportFormat::Json)).unwrap();
+        assert_eq!(json["contracts"]["acl-v2"]["code_id"], json!(7));
+        assert!(export
+            .render(ExportFormat::Env)
+            .contains("ACL_V2_ADDRESS=archway1acl\nACL_V2_CODE_ID=7\n"));
+        assert!(export
+            .render(ExportFormat::Ts)
+            .contains(r#"  "acl-v2": { address: "archway1acl", codeId: 7 },"#));
+        assert!(export.render(ExportFormat::Yaml).ends_with(
+            "contracts:\n  \"acl-v2\":\n    address: \"archway1acl\"\n    code_id: 7\n"
+        ));
+    }
+}
@@ -0,0 +1,277 @@
+//! Placeholder references between autodeploy steps. `$<name>` resolves to the
+//! address of a step and `#<name>` to its code ID, where `<name>` is the step id
+//! without its leading sigil. Since every contract is stored before anything is
+//! instantiated, only address references constrain the deployment order. The
+//! `{{ steps.<name>.address }}` templates of [`super::template`] count as address
+//! references as well. A `$word` or `#word` naming neither a step nor `account_id`,
+//! such as `$USD` in a memo, is plain text and left as it is.
+
+use std::{collections::HashMap, path::Path};
+
+use regex::{Captures, Regex};
+
+use crate::error::WarpError;
+
+use super::{project_config::AutoDeployStep, template};
+
+/// `$account_id` resolves to the deployer address
+pub const ACCOUNT_PLACEHOLDER: &str = "account_id";
+
+fn placeholder_regex() -> Regex {
+    Regex::new(r"([$#])([A-Za-z_][A-Za-z0-9_]*)").unwrap()
+}
+
+/// Every `(sigil, name)` placeholder in `msg`, matched on whole identifiers so that
+/// `$_acl` does not match inside `$_acl_v2`
+pub fn placeholders(msg: &str) -> Vec<(char, String)> {
+    placeholder_regex()
+        .captures_iter(msg)
+        .map(|x| (x[1].chars().next().unwrap(), x[2].to_owned()))
+        .collect()
+}
+
+/// Replaces every placeholder `resolve` knows about and leaves the others untouched
+pub fn render<F>(msg: &str, mut resolve: F) -> String
+where
+    F: FnMut(char, &str) -> Option<String>,
+{
+    placeholder_regex()
+        .replace_all(msg, |x: &Captures| {
+            resolve(x[1].chars().next().unwrap(), &x[2]).unwrap_or_else(|| x[0].to_owned())
+        })
+        .into_owned()
+}
+
+/// Deployment order of the autodeploy steps
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeploymentGraph {
+    /// Step indices in the order they have to be instantiated or migrated
+    pub order: Vec<usize>,
+    /// Stage of every step; steps in the same stage don't depend on each other
+    pub stages: Vec<usize>,
+}
+
+impl DeploymentGraph {
+    pub fn build(steps: &[AutoDeployStep], project_root: &Path) -> Result<Self, WarpError> {
+        let mut indices = HashMap::new();
+        for (i, step) in steps.iter().enumerate() {
+            if indices.insert(step.reference(), i).is_some() {
+                return Err(WarpError::DuplicateStepId(step.id.clone()));
+            }
+        }
+
+        let mut dependencies: Vec<Vec<usize>> = vec![vec![]; steps.len()];
+        for (i, step) in steps.iter().enumerate() {
+            for action in step.after.iter() {
+                let target = action.target(step, steps).ok_or_else(|| {
+                    WarpError::UnknownStepReference(
+                        step.id.clone(),
+                        action.contract.clone().unwrap_or_default(),
+                    )
+                })?;
+                if step.store_only || target.store_only {
+                    return Err(WarpError::StoreOnlyReference(
+                        step.id.clone(),
+                        target.id.clone(),
+                    ));
+                }
+            }
+            // After-deploy actions run once every contract exists, so their references
+            // are checked but don't order the steps
+            let mut messages = vec![];
+            for msg in [Some(&step.init_msg), step.migrate_msg.as_ref()]
+                .into_iter()
+                .flatten()
+            {
+                messages.push((msg.to_json(project_root)?, true));
+            }
+            messages.extend(step.admin_json().map(|x| (x, true)));
+            for action in step.after.iter() {
+                messages.push((action.msg.to_json(project_root)?, false));
+            }
+            let mut references = vec![];
+            for (msg, ordering) in messages.iter() {
+                let ordering = *ordering;
+                for (sigil, name) in placeholders(msg) {
+                    if indices.contains_key(name.as_str()) {
+                        references.push((
+                            ordering && sigil == '$',
+                            sigil == '$',
+                            format!("{}{}", sigil, name),
+                            name,
+                        ));
+                    }
+                }
+                for (field, name) in template::step_references(msg) {
+                    let placeholder = format!("steps.{}.{}", name, field);
+                    let name = match indices.contains_key(name.as_str()) {
+                        true => name,
+                        false => steps
+                            .iter()
+                            .map(|x| x.reference())
+                            .find(|x| template::matches_step(x, &name))
+                            .map_or(name, str::to_owned),
+                    };
+                    references.push((
+                        ordering && field == "address",
+                        field == "address",
+                        placeholder,
+                        name,
+                    ));
+                }
+            }
+            for (ordering, is_address, placeholder, name) in references {
+                let target = *indices.get(name.as_str()).ok_or_else(|| {
+                    WarpError::UnknownStepReference(step.id.clone(), placeholder.clone())
+                })?;
+                if is_address {
+                    if steps[target].store_only {
+                        return Err(WarpError::StoreOnlyReference(step.id.clone(), placeholder));
+                    }
+                    if ordering && !dependencies[i].contains(&target) {
+                        dependencies[i].push(target);
+                    }
+                }
+            }
+        }
+
+        // Kahn's algorithm, picking the earliest declared step whenever there's a choice
+        let mut stages = vec![0; steps.len()];
+        let mut done = vec![false; steps.len()];
+        let mut order = vec![];
+        while order.len() < steps.len() {
+            let next = (0..steps.len())
+                .find(|&i| !done[i] && dependencies[i].iter().all(|&dep| done[dep]));
+            let Some(next) = next else {
+                let cycle: Vec<&str> = (0..steps.len())
+                    .filter(|&i| !done[i])
+                    .map(|i| steps[i].id.as_str())
+                    .collect();
+                return Err(WarpError::DeploymentCycle(cycle.join(", ")));
+            };
+            stages[next] = dependencies[next]
+                .iter()
+                .map(|&dep| stages[dep] + 1)
+                .max()
+                .unwrap_or(0);
+            done[next] = true;
+            order.push(next);
+        }
+        Ok(Self { order, stages })
+    }
+
+    /// Steps in deployment order
+    pub fn ordered<'a>(
+        &'a self,
+        steps: &'a [AutoDeployStep],
+    ) -> impl Iterator<Item = &'a AutoDeployStep> + 'a {
+        self.order.iter().map(move |&i| &steps[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::project_config::AfterAction;
+
+    fn step(id: &str, init_msg: &str) -> AutoDeployStep {
+        AutoDeployStep {
+            id: id.to_owned(),
+            contract: format!("artifacts/{}.wasm", id),
+            label: id.to_owned(),
+            store_only: false,
+            admin: None,
+            migrate_msg: None,
+            rollback_msg: None,
+            init_msg: init_msg.into(),
+            coins: None,
+            fees: None,
+            after: vec![],
+        }
+    }
+
+    #[test]
+    fn matches_placeholders_on_token_boundaries() {
+        let rendered = render(
+            r#"{"a":"$_acl","b":"$_acl_v2","c":#_acl}"#,
+            |sigil, name| match (sigil, name) {
+                ('$', "_acl") => Some("addr1".to_owned()),
+                ('#', "_acl") => Some("7".to_owned()),
+                _ => None,
+            },
+        );
+        assert_eq!(rendered, r#"{"a":"addr1","b":"$_acl_v2","c":7}"#);
+    }
+
+    #[test]
+    fn orders_steps_by_address_references() {
+        let steps = [
+            step(
+                "$_factory",
+                r#"{"acl":"$_acl","system":"$_system","code":#_pair}"#,
+            ),
+            step(
+                "$_system",
+                r#"{"acl":"{{ steps.acl.address }}","owner":"$account_id"}"#,
+            ),
+            step("$_acl", "{}"),
+            step("$_pair", "{}"),
+        ];
+        let graph = DeploymentGraph::build(&steps, Path::new(".")).unwrap();
+        assert_eq!(graph.order, vec![2, 1, 0, 3]);
+        assert_eq!(graph.stages, vec![2, 1, 0, 0]);
+    }
+
+    #[test]
+    fn rejects_cycles_and_unknown_references() {
+        let steps = [step("$_a", r#"{"b":"$_b"}"#), step("$_b", r#"{"a":"$_a"}"#)];
+        assert!(matches!(
+            DeploymentGraph::build(&steps, Path::new(".")),
+            Err(WarpError::DeploymentCycle(_))
+        ));
+
+        let steps = [step("$_a", r#"{"b":"{{ steps.acl.address }}"}"#)];
+        assert!(matches!(
+            DeploymentGraph::build(&steps, Path::new(".")),
+            Err(WarpError::UnknownStepReference(step, placeholder)) if step == "$_a" && placeholder == "steps.acl.address"
+        ));
+    }
+
+    #[test]
+    fn leaves_other_sigil_words_alone() {
+        let steps = [
+            step(
+                "$_a",
+                r#"{"memo":"price in $USD #hashtag","owner":"$account_id"}"#,
+            ),
+            step("$_b", r#"{"a":"$_a"}"#),
+        ];
+        let graph = DeploymentGraph::build(&steps, Path::new(".")).unwrap();
+        assert_eq!(graph.order, vec![0, 1]);
+
+        let rendered = render(
+            r#"{"memo":"price in $USD #hashtag","a":"$_a"}"#,
+            |sigil, name| (sigil == '$' && name == "_a").then(|| "addr1".to_owned()),
+        );
+        assert_eq!(rendered, r#"{"memo":"price in $USD #hashtag","a":"addr1"}"#);
+    }
+
+    #[test]
+    fn after_actions_check_references_without_ordering() {
+        let mut steps = [step("$_a", r#"{"b":"$_b"}"#), step("$_b", "{}")];
+        steps[1].after.push(AfterAction {
+            id: None,
+            contract: None,
+            always: false,
+            msg: r#"{"set_a":{"address":"{{ steps.a.address }}"}}"#.into(),
+        });
+        let graph = DeploymentGraph::build(&steps, Path::new(".")).unwrap();
+        assert_eq!(graph.order, vec![1, 0]);
+
+        steps[1].after[0].contract = Some("$_c".to_owned());
+        assert!(matches!(
+            DeploymentGraph::build(&steps, Path::new(".")),
+            Err(WarpError::UnknownStepReference(step, target)) if step == "$_b" && target == "$_c"
+        ));
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::WarpError;
+
+pub const JOURNAL_FILENAME: &str = "deploy-journal.toml";
+
+/// Transactions of an unfinished `warp deploy`, written after every step so a failed
+/// deployment can be continued with `warp deploy --resume`. Lives in `<project>/.warp/`
+/// and is removed once the deployment completes.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct DeploymentJournal {
+    /// Deployment.toml key of the deployment, see [`ProjectConfig::deployment_key`]
+    ///
+    /// [`ProjectConfig::deployment_key`]: super::project_config::ProjectConfig::deployment_key
+    pub chain_id: String,
+    #[serde(default)]
+    pub entries: Vec<JournalEntry>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct JournalEntry {
+    /// Autodeploy step id
+    pub step: String,
+    pub action: JournalAction,
+    pub txhash: String,
+    pub code_id: Option<String>,
+    pub contract_address: Option<String>,
+    #[serde(default)]
+    pub checksum: Option<String>,
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Key of the after-deploy action, for `Execute` entries
+    #[serde(default)]
+    pub action_id: Option<String>,
+    /// RFC 3339 time the transaction went through
+    #[serde(default)]
+    pub timestamp: String,
+    /// Broadcast, but not seen in a block yet. Recorded this way first so an interrupted
+    /// wait can't make `--resume` send the transaction twice.
+    #[serde(default)]
+    pub pending: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalAction {
+    Store,
+    Instantiate,
+    Migrate,
+    Execute,
+}
+
+impl DeploymentJournal {
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(".warp").join(JOURNAL_FILENAME)
+    }
+
+    pub fn load(project_root: &Path) -> Result<Option<Self>, WarpError> {
+        let path = Self::path(project_root);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let mut journal: Self = toml::from_str(&fs::read_to_string(&path)?)?;
+        journal.path = path;
+        Ok(Some(journal))
+    }
+
+    /// Starts an empty journal for a new deployment. Nothing is written until the
+    /// first transaction is recorded.
+    pub fn start(project_root: &Path, chain_id: &str) -> Self {
+        Self {
+            chain_id: chain_id.to_owned(),
+            entries: vec![],
+            path: Self::path(project_root),
+        }
+    }
+
+    /// Appends `entry` and writes the journal to disk right away
+    pub fn record(&mut self, entry: JournalEntry) -> Result<(), WarpError> {
+        self.entries.push(entry);
+        self.save()
+    }
+
+    /// Replaces the entry recorded for the same transaction, e.g. once it is confirmed
+    pub fn update(&mut self, entry: JournalEntry) -> Result<(), WarpError> {
+        if let Some(recorded) = self.entries.iter_mut().find(|x| x.same_action(&entry)) {
+            *recorded = entry;
+        }
+        self.save()
+    }
+
+    /// Forgets `entry`, whose transaction failed, so it is sent again
+    pub fn discard(&mut self, entry: &JournalEntry) -> Result<(), WarpError> {
+        self.entries.retain(|x| !x.same_action(entry));
+        self.save()
+    }
+
+    /// Result of `action` for `step`, if it already went through
+    pub fn find(&self, step: &str, action: JournalAction) -> Option<&JournalEntry> {
+        self.entries
+            .iter()
+            .find(|x| x.step == step && x.action == action)
+    }
+
+    /// The `Execute` entry of an after-deploy action, if it already went through
+    pub fn find_execute(&self, step: &str, action_id: &str) -> Option<&JournalEntry> {
+        self.entries.iter().find(|x| {
+            x.step == step
+                && x.action == JournalAction::Execute
+                && x.action_id.as_deref() == Some(action_id)
+        })
+    }
+
+    /// Deletes the journal once the deployment finished
+    pub fn finish(self) -> Result<(), WarpError> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+
+    fn save(&self) -> Result<(), WarpError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+impl JournalEntry {
+    fn same_action(&self, other: &JournalEntry) -> bool {
+        self.step == other.step && self.action == other.action && self.action_id == other.action_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_entries_as_they_happen() {
+        let root = std::env::temp_dir().join(format!("warp-journal-{}", std::process::id()));
+        assert_eq!(DeploymentJournal::load(&root).unwrap(), None);
+
+        let mut journal = DeploymentJournal::start(&root, "constantine-3");
+        journal
+            .record(JournalEntry {
+                step: "$_acl".to_owned(),
+                action: JournalAction::Store,
+                txhash: "ABC".to_owned(),
+                code_id: None,
+                contract_address: None,
+                checksum: None,
+                label: None,
+                action_id: None,
+                timestamp: "2024-01-01T00:00:00Z".to_owned(),
+                pending: true,
+            })
+            .unwrap();
+        let mut confirmed = journal.entries[0].clone();
+        confirmed.code_id = Some("7".to_owned());
+        confirmed.pending = false;
+        journal.update(confirmed).unwrap();
+
+        let loaded = DeploymentJournal::load(&root).unwrap().unwrap();
+        assert_eq!(loaded, journal);
+        let entry = loaded.find("$_acl", JournalAction::Store).unwrap();
+        assert_eq!(entry.code_id.as_deref(), Some("7"));
+        assert!(!entry.pending);
+        assert!(loaded.find("$_acl", JournalAction::Instantiate).is_none());
+
+        loaded.finish().unwrap();
+        assert!(!DeploymentJournal::path(&root).exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
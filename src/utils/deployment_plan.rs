@@ -0,0 +1,162 @@
+use owo_colors::OwoColorize;
+use serde::Serialize;
+
+use crate::chains::fees::FeeEstimate;
+
+/// What `warp deploy` would do, computed without sending any transaction
+#[derive(Serialize, Clone, Debug)]
+pub struct DeploymentPlan {
+    pub chain_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+    /// Deployer address, or a placeholder if it can't be resolved without the keyring password
+    pub deployer: String,
+    pub steps: Vec<PlannedStep>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PlannedStep {
+    pub id: String,
+    /// Steps in the same stage don't depend on each other's addresses
+    pub stage: usize,
+    pub contract: String,
+    pub action: PlannedAction,
+    pub label: String,
+    /// Address from Deployment.toml for contracts that will be migrated
+    pub contract_address: Option<String>,
+    /// Admin of contracts that will be instantiated, `none` for an immutable one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub admin: Option<String>,
+    /// Rendered init or migrate message
+    pub message: Option<String>,
+    /// Fee of the code upload, unless the wasm did not change
+    pub store_fee: Option<FeeEstimate>,
+    /// Fee of the instantiate or migrate transaction
+    pub fee: Option<FeeEstimate>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub after: Vec<PlannedAfterAction>,
+}
+
+/// An `[[autodeploy.steps.after]]` action of a step
+#[derive(Serialize, Clone, Debug)]
+pub struct PlannedAfterAction {
+    pub id: String,
+    /// Id of the step whose contract is called
+    pub contract: String,
+    pub message: String,
+    /// Ran on the current instantiation already and isn't marked `always`
+    pub skipped: bool,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlannedAction {
+    StoreOnly,
+    Instantiate,
+    Migrate,
+    /// Deployed with the same wasm already, so nothing is sent
+    Unchanged,
+}
+
+impl PlannedAction {
+    fn describe(&self) -> &'static str {
+        match self {
+            PlannedAction::StoreOnly => "store",
+            PlannedAction::Instantiate => "store + instantiate",
+            PlannedAction::Migrate => "store + migrate",
+            PlannedAction::Unchanged => "unchanged",
+        }
+    }
+}
+
+impl DeploymentPlan {
+    pub fn print_table(&self) {
+        let target = match &self.environment {
+            Some(environment) => format!("{} ({})", self.chain_id, environment),
+            None => self.chain_id.clone(),
+        };
+        println!(
+            "Deployment plan for {} (deployer: {})",
+            target.bright_blue(),
+            self.deployer.bright_blue()
+        );
+        let header = ["STAGE", "ID", "ACTION", "CONTRACT", "STORE FEE", "TX FEE"].map(String::from);
+        let rows: Vec<[String; 6]> = self
+            .steps
+            .iter()
+            .map(|step| {
+                [
+                    step.stage.to_string(),
+                    step.id.clone(),
+                    step.action.describe().to_owned(),
+                    step.contract.clone(),
+                    step.store_fee
+                        .as_ref()
+                        .map(describe_fee)
+                        .unwrap_or_default(),
+                    step.fee.as_ref().map(describe_fee).unwrap_or_default(),
+                ]
+            })
+            .collect();
+        let mut widths = header.clone().map(|x| x.len());
+        for row in rows.iter() {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(cell.len());
+            }
+        }
+        let format_row = |row: &[String; 6]| {
+            row.iter()
+                .zip(widths.iter())
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+        };
+        println!(" {}", format_row(&header).bold());
+        for row in rows.iter() {
+            println!(" {}", format_row(row));
+        }
+
+        for step in self.steps.iter() {
+            println!();
+            println!(
+                " {} {} ({})",
+                "=>".bright_yellow(),
+                step.id.bright_blue(),
+                step.action.describe()
+            );
+            println!("    label:   {}", step.label);
+            if let Some(address) = &step.contract_address {
+                println!("    address: {}", address.bright_cyan());
+            }
+            if let Some(admin) = &step.admin {
+                println!("    admin:   {}", admin.bright_cyan());
+            }
+            if let Some(message) = &step.message {
+                println!("    message: {}", message.bright_yellow());
+            }
+            for action in step.after.iter() {
+                let status = if action.skipped { " (done)" } else { "" };
+                println!(
+                    "    after:   {} -> {}{}: {}",
+                    action.id,
+                    action.contract.bright_blue(),
+                    status,
+                    action.message.bright_yellow()
+                );
+            }
+        }
+    }
+}
+
+fn describe_fee(estimate: &FeeEstimate) -> String {
+    let prices = if estimate.gas_prices.is_empty() {
+        "no gas price".to_owned()
+    } else {
+        format!("@ {}", estimate.gas_prices)
+    };
+    match (&estimate.fee, estimate.gas_limit, estimate.gas_adjustment) {
+        (Some(fee), Some(limit), _) => format!("{} ({} gas)", fee, limit),
+        (_, _, Some(adjustment)) => format!("simulated x{} {}", adjustment, prices),
+        _ => format!("at runtime {}", prices),
+    }
+}
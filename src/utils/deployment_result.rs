@@ -1,4 +1,4 @@
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{collections::BTreeMap, fs, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
 
@@ -8,9 +8,75 @@ use super::project_config::ProjectConfig;
 
 pub const CONFIG_FILENAME: &str = "Deployment.toml";
 
-#[derive(Serialize, Deserialize, Clone, Default)]
+/// Deployed contracts per chain ID and autodeploy step id
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
 pub struct DeploymentResult {
-    pub deployment: HashMap<String, HashMap<String, String>>,
+    pub deployment: BTreeMap<String, BTreeMap<String, DeploymentRecord>>,
+}
+
+/// Everything Warp knows about a deployed contract
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct DeploymentRecord {
+    pub address: String,
+    /// Code ID currently running at `address`
+    pub code_id: Option<String>,
+    /// Hex-encoded SHA-256 of the wasm file behind `code_id`
+    pub checksum: Option<String>,
+    pub label: Option<String>,
+    /// Address allowed to migrate the contract, `None` once it has no admin
+    pub admin: Option<String>,
+    pub deployer: Option<String>,
+    pub store_tx: Option<String>,
+    pub instantiate_tx: Option<String>,
+    /// RFC 3339 time of the instantiation
+    pub deployed_at: Option<String>,
+    pub warp_version: Option<String>,
+    /// Migrations of this contract, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<MigrationRecord>,
+    /// After-deploy actions run on this instantiation
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<ActionRecord>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct ActionRecord {
+    /// Key of the action, see [`AfterAction::key`]
+    ///
+    /// [`AfterAction::key`]: super::project_config::AfterAction::key
+    pub id: String,
+    /// Address of the called contract
+    pub contract: String,
+    pub txhash: String,
+    pub executed_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct MigrationRecord {
+    pub from_code_id: Option<String>,
+    pub code_id: String,
+    pub checksum: Option<String>,
+    pub store_tx: Option<String>,
+    pub migrate_tx: String,
+    pub deployer: Option<String>,
+    pub migrated_at: String,
+    pub warp_version: String,
+    /// Set when `warp rollback` went back to an earlier code ID
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub rollback: bool,
+}
+
+/// Deployment.toml as written by Warp 0.3 and earlier, mapping step ids to bare addresses
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredRecord {
+    Legacy(String),
+    Record(Box<DeploymentRecord>),
+}
+
+#[derive(Deserialize)]
+struct StoredDeploymentResult {
+    deployment: BTreeMap<String, BTreeMap<String, StoredRecord>>,
 }
 
 impl DeploymentResult {
@@ -19,13 +85,16 @@ impl DeploymentResult {
         Ok(path.join(CONFIG_FILENAME).exists())
     }
 
+    /// Parses Deployment.toml, rewriting files in the old address-only format in place
     pub fn parse() -> Result<(PathBuf, Self), WarpError> {
         let mut current_dir = std::env::current_dir()?;
-        let config: Self;
         loop {
             let project_file = current_dir.join(CONFIG_FILENAME);
             if project_file.exists() {
-                config = toml::from_str(fs::read_to_string(project_file)?.as_str())?;
+                let (config, migrated) = Self::from_toml(&fs::read_to_string(&project_file)?)?;
+                if migrated {
+                    fs::write(&project_file, toml::to_string_pretty(&config)?)?;
+                }
                 return Ok((current_dir, config));
             }
             let parent = current_dir.parent();
@@ -37,7 +106,37 @@ impl DeploymentResult {
         }
     }
 
-    pub fn network(&mut self, id: &str) -> &mut HashMap<String, String> {
+    /// Also returns whether any entry was in the legacy format
+    fn from_toml(content: &str) -> Result<(Self, bool), WarpError> {
+        let stored: StoredDeploymentResult = toml::from_str(content)?;
+        let mut migrated = false;
+        let deployment = stored
+            .deployment
+            .into_iter()
+            .map(|(chain_id, records)| {
+                let records = records
+                    .into_iter()
+                    .map(|(id, record)| match record {
+                        StoredRecord::Legacy(address) => {
+                            migrated = true;
+                            (
+                                id,
+                                DeploymentRecord {
+                                    address,
+                                    ..Default::default()
+                                },
+                            )
+                        }
+                        StoredRecord::Record(record) => (id, *record),
+                    })
+                    .collect();
+                (chain_id, records)
+            })
+            .collect();
+        Ok((Self { deployment }, migrated))
+    }
+
+    pub fn network(&mut self, id: &str) -> &mut BTreeMap<String, DeploymentRecord> {
         self.deployment.entry(id.to_string()).or_default()
     }
 
@@ -47,3 +146,190 @@ impl DeploymentResult {
         Ok(())
     }
 }
+
+/// Current time in RFC 3339 format, as stored in deployment records
+pub fn timestamp() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+impl DeploymentRecord {
+    /// Appends a migration to the history and points the record at the new code
+    pub fn migrated(&mut self, migration: MigrationRecord) {
+        self.code_id = Some(migration.code_id.clone());
+        self.checksum = migration.checksum.clone();
+        self.history.push(migration);
+    }
+
+    /// Fails unless `deployer` may migrate the contract. `on_chain` is the admin from
+    /// the contract info on chain (empty for none), which wins over the recorded one.
+    pub fn ensure_admin(
+        &self,
+        id: &str,
+        deployer: &str,
+        on_chain: Option<String>,
+    ) -> Result<(), WarpError> {
+        let admin = match on_chain {
+            Some(admin) => admin,
+            // Without the chain, only a recorded admin can rule the deployer out
+            None => match &self.admin {
+                Some(admin) => admin.clone(),
+                None => return Ok(()),
+            },
+        };
+        match admin.as_str() {
+            "" => Err(WarpError::ImmutableContract(id.to_owned())),
+            admin if admin != deployer => Err(WarpError::NotContractAdmin(
+                id.to_owned(),
+                admin.to_owned(),
+                deployer.to_owned(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether the after-deploy action `id` already ran on this instantiation against
+    /// the contract at `contract` (a re-instantiated target gets the action again)
+    pub fn action_done(&self, id: &str, contract: &str) -> bool {
+        self.actions
+            .iter()
+            .any(|x| x.id == id && x.contract == contract)
+    }
+
+    /// Records a run of an after-deploy action, replacing earlier runs of it
+    pub fn action_executed(&mut self, action: ActionRecord) {
+        self.actions.retain(|x| x.id != action.id);
+        self.actions.push(action);
+    }
+
+    /// Code IDs the contract has run, in the order they were first deployed
+    pub fn code_ids(&self) -> Vec<&str> {
+        let first = match self.history.first() {
+            Some(migration) => migration.from_code_id.as_deref(),
+            None => self.code_id.as_deref(),
+        };
+        let mut code_ids: Vec<&str> = vec![];
+        for code_id in first
+            .into_iter()
+            .chain(self.history.iter().map(|x| x.code_id.as_str()))
+        {
+            if !code_ids.contains(&code_id) {
+                code_ids.push(code_id);
+            }
+        }
+        code_ids
+    }
+
+    /// Code ID deployed `versions` versions before the current one. Rollbacks don't count
+    /// as versions, so rolling back twice steps two versions back.
+    pub fn previous_code_id(&self, versions: usize) -> Option<&str> {
+        let code_ids = self.code_ids();
+        let current = code_ids
+            .iter()
+            .position(|x| Some(*x) == self.code_id.as_deref())?;
+        code_ids.get(current.checked_sub(versions)?).copied()
+    }
+
+    /// Checksum last recorded for `code_id`
+    pub fn checksum_of(&self, code_id: &str) -> Option<String> {
+        if self.code_id.as_deref() == Some(code_id) && self.checksum.is_some() {
+            return self.checksum.clone();
+        }
+        self.history
+            .iter()
+            .rev()
+            .find(|x| x.code_id == code_id && x.checksum.is_some())
+            .and_then(|x| x.checksum.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_address_only_files() {
+        let legacy = r#"
+            [deployment.constantine-3]
+            "$_acl" = "archway1acl"
+
+            [deployment.constantine-3."$_factory"]
+            address = "archway1factory"
+            code_id = "12"
+        "#;
+        let (mut result, migrated) = DeploymentResult::from_toml(legacy).unwrap();
+        assert!(migrated);
+        let network = &result.deployment["constantine-3"];
+        assert_eq!(network["$_acl"].address, "archway1acl");
+        assert_eq!(network["$_acl"].code_id, None);
+        assert_eq!(network["$_factory"].code_id.as_deref(), Some("12"));
+
+        let factory = result
+            .network("constantine-3")
+            .get_mut("$_factory")
+            .unwrap();
+        factory.migrated(MigrationRecord {
+            from_code_id: factory.code_id.clone(),
+            code_id: "13".to_owned(),
+            migrate_tx: "ABC".to_owned(),
+            ..Default::default()
+        });
+        assert_eq!(factory.code_id.as_deref(), Some("13"));
+
+        let rewritten = toml::to_string_pretty(&result).unwrap();
+        let (reparsed, migrated) = DeploymentResult::from_toml(&rewritten).unwrap();
+        assert!(!migrated);
+        assert_eq!(reparsed, result);
+        assert_eq!(
+            reparsed.deployment["constantine-3"]["$_factory"].history[0].from_code_id,
+            Some("12".to_owned())
+        );
+    }
+
+    #[test]
+    fn walks_back_through_code_versions() {
+        let migration = |from: &str, to: &str, rollback| MigrationRecord {
+            from_code_id: Some(from.to_owned()),
+            code_id: to.to_owned(),
+            checksum: Some(format!("sum{}", to)),
+            rollback,
+            ..Default::default()
+        };
+        let mut record = DeploymentRecord {
+            code_id: Some("1".to_owned()),
+            checksum: Some("sum1".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(record.previous_code_id(1), None);
+
+        record.migrated(migration("1", "2", false));
+        record.migrated(migration("2", "3", false));
+        assert_eq!(record.code_ids(), vec!["1", "2", "3"]);
+        assert_eq!(record.previous_code_id(1), Some("2"));
+        assert_eq!(record.previous_code_id(2), Some("1"));
+        assert_eq!(record.previous_code_id(3), None);
+
+        record.migrated(migration("3", "2", true));
+        assert_eq!(record.previous_code_id(1), Some("1"));
+        assert_eq!(record.checksum_of("3").as_deref(), Some("sum3"));
+        assert_eq!(record.checksum_of("1"), None);
+    }
+
+    #[test]
+    fn only_the_admin_may_migrate() {
+        let mut record = DeploymentRecord {
+            admin: Some("archway1me".to_owned()),
+            ..Default::default()
+        };
+        assert!(record.ensure_admin("$_acl", "archway1me", None).is_ok());
+        assert!(matches!(
+            record.ensure_admin("$_acl", "archway1me", Some("archway1dao".to_owned())),
+            Err(WarpError::NotContractAdmin(_, admin, _)) if admin == "archway1dao"
+        ));
+        assert!(matches!(
+            record.ensure_admin("$_acl", "archway1me", Some(String::new())),
+            Err(WarpError::ImmutableContract(_))
+        ));
+        record.admin = None;
+        assert!(record.ensure_admin("$_acl", "archway1me", None).is_ok());
+    }
+}
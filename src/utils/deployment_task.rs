@@ -5,4 +5,9 @@ pub struct DeploymentTask<'a> {
     pub step: &'a AutoDeployStep,
     pub code_id: Option<String>,
     pub contract_address: Option<String>,
+    /// Checksum of the uploaded wasm file
+    pub checksum: Option<String>,
+    pub store_tx: Option<String>,
+    /// The deployed code already matches the wasm file, so it was neither stored nor migrated
+    pub unchanged: bool,
 }
@@ -1,4 +1,7 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use sha2::{Digest, Sha256};
 
 use crate::error::WarpError;
 
@@ -12,3 +15,61 @@ where
 
     Ok(())
 }
+
+/// Recursively copies `source` into `target`, returning the paths of the copied files
+pub fn copy_dir(source: &Path, target: &Path) -> Result<Vec<PathBuf>, WarpError> {
+    std::fs::create_dir_all(target)?;
+    let mut files = vec![];
+    for entry in std::fs::read_dir(source)?.flatten() {
+        let path = entry.path();
+        let destination = target.join(entry.file_name());
+        if path.is_dir() {
+            files.extend(copy_dir(&path, &destination)?);
+        } else {
+            std::fs::copy(&path, &destination)?;
+            files.push(destination);
+        }
+    }
+    Ok(files)
+}
+
+/// Hex-encoded SHA-256 digest of a file, as used for wasm checksums
+pub fn sha256_hex<P>(path: P) -> Result<String, WarpError>
+where
+    P: AsRef<Path>,
+{
+    let digest = Sha256::digest(std::fs::read(path)?);
+    Ok(digest.iter().map(|x| format!("{:02x}", x)).collect())
+}
+
+/// Brings a wasm checksum reported by a node (hex in any case, optionally `0x`-prefixed,
+/// or base64) into the lowercase hex form returned by [`sha256_hex`]
+pub fn normalize_checksum(value: &str) -> Option<String> {
+    let value = value.trim().trim_matches('"');
+    let hex = value.strip_prefix("0x").unwrap_or(value);
+    if hex.len() == 64 && hex.chars().all(|x| x.is_ascii_hexdigit()) {
+        return Some(hex.to_lowercase());
+    }
+    let bytes = STANDARD.decode(value).ok().filter(|x| x.len() == 32)?;
+    Some(bytes.iter().map(|x| format!("{:02x}", x)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_node_checksums() {
+        let hex = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+        assert_eq!(normalize_checksum(hex).as_deref(), Some(hex));
+        assert_eq!(
+            normalize_checksum(&format!("0x{}", hex.to_uppercase())).as_deref(),
+            Some(hex)
+        );
+        assert_eq!(
+            normalize_checksum("n4bQgYhMfWWaL+qgxVrQFaO/TxsrC4Is0V1sFbDwCgg=").as_deref(),
+            Some(hex)
+        );
+        assert_eq!(normalize_checksum("not a checksum"), None);
+    }
+}
@@ -0,0 +1,467 @@
+//! Validation of JSON messages against the JSON schemas generated by `cosmwasm-schema`.
+//! Covers the draft-07 keywords that schemars emits: `$ref` into `definitions`, `type`,
+//! `enum`/`const`, `allOf`/`anyOf`/`oneOf`, object `properties`/`required`/
+//! `additionalProperties`, array `items`/`minItems`/`maxItems`, string lengths and
+//! patterns, and numeric bounds including the integer `format`s.
+
+use regex::Regex;
+use serde_json::Value;
+
+/// A part of a message that doesn't match the schema
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemaViolation {
+    /// JSON pointer (RFC 6901) of the offending value
+    pub pointer: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "(root)"
+        } else {
+            &self.pointer
+        };
+        write!(f, "{}: {}", pointer, self.message)
+    }
+}
+
+/// Every violation of `schema` in `instance`. With `placeholders`, strings like
+/// `<code_id:#_acl>` that stand in for values not known yet are accepted anywhere.
+pub fn validate(schema: &Value, instance: &Value, placeholders: bool) -> Vec<SchemaViolation> {
+    let validator = Validator {
+        root: schema,
+        placeholders,
+    };
+    let mut violations = vec![];
+    validator.check(schema, instance, "", &mut violations);
+    violations
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    placeholders: bool,
+}
+
+impl<'a> Validator<'a> {
+    fn check(
+        &self,
+        schema: &'a Value,
+        instance: &Value,
+        pointer: &str,
+        out: &mut Vec<SchemaViolation>,
+    ) {
+        let mut fail = |message: String| {
+            out.push(SchemaViolation {
+                pointer: pointer.to_owned(),
+                message,
+            })
+        };
+        let schema = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => return fail("no value is allowed here".to_owned()),
+            Value::Object(schema) => schema,
+            _ => return,
+        };
+        if self.placeholders && is_placeholder(instance) {
+            return;
+        }
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            match self.resolve(reference) {
+                Some(target) => return self.check(target, instance, pointer, out),
+                None => return fail(format!("unresolvable schema reference '{}'", reference)),
+            }
+        }
+
+        if let Some(types) = schema.get("type") {
+            let allowed: Vec<&str> = match types {
+                Value::String(x) => vec![x.as_str()],
+                Value::Array(x) => x.iter().filter_map(Value::as_str).collect(),
+                _ => vec![],
+            };
+            if !allowed.is_empty() && !allowed.iter().any(|x| has_type(instance, x)) {
+                return fail(format!(
+                    "expected {}, found {}",
+                    allowed.join(" or "),
+                    type_name(instance)
+                ));
+            }
+        }
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            if !values.contains(instance) {
+                return fail(format!("expected one of {}", join_values(values)));
+            }
+        }
+        if let Some(value) = schema.get("const") {
+            if value != instance {
+                return fail(format!("expected {}", value));
+            }
+        }
+
+        if let Some(schemas) = schema.get("allOf").and_then(Value::as_array) {
+            for schema in schemas {
+                self.check(schema, instance, pointer, out);
+            }
+        }
+        for (keyword, exactly_one) in [("anyOf", false), ("oneOf", true)] {
+            if let Some(schemas) = schema.get(keyword).and_then(Value::as_array) {
+                self.check_variants(schemas, exactly_one, instance, pointer, out);
+            }
+        }
+
+        match instance {
+            Value::Object(object) => {
+                let properties = schema.get("properties").and_then(Value::as_object);
+                if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                    for key in required.iter().filter_map(Value::as_str) {
+                        if !object.contains_key(key) {
+                            out.push(SchemaViolation {
+                                pointer: pointer.to_owned(),
+                                message: format!("missing required property '{}'", key),
+                            });
+                        }
+                    }
+                }
+                for (key, value) in object {
+                    let child = format!("{}/{}", pointer, escape(key));
+                    match (
+                        properties.and_then(|x| x.get(key)),
+                        schema.get("additionalProperties"),
+                    ) {
+                        (Some(schema), _) => self.check(schema, value, &child, out),
+                        (None, Some(Value::Bool(false))) => out.push(SchemaViolation {
+                            pointer: child,
+                            message: match properties {
+                                Some(x) if !x.is_empty() => format!(
+                                    "unknown property '{}', expected one of {}",
+                                    key,
+                                    x.keys()
+                                        .map(|x| format!("'{}'", x))
+                                        .collect::<Vec<_>>()
+                                        .join(", ")
+                                ),
+                                _ => format!("unknown property '{}'", key),
+                            },
+                        }),
+                        (None, Some(schema)) => self.check(schema, value, &child, out),
+                        (None, None) => (),
+                    }
+                }
+            }
+            Value::Array(items) => {
+                let count = items.len() as u64;
+                if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                    if count < min {
+                        out.push(
+                            self.violation(pointer, format!("expected at least {} items", min)),
+                        );
+                    }
+                }
+                if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                    if count > max {
+                        out.push(
+                            self.violation(pointer, format!("expected at most {} items", max)),
+                        );
+                    }
+                }
+                match schema.get("items") {
+                    Some(Value::Array(schemas)) => {
+                        for (i, (item, schema)) in items.iter().zip(schemas).enumerate() {
+                            self.check(schema, item, &format!("{}/{}", pointer, i), out);
+                        }
+                    }
+                    Some(schema) => {
+                        for (i, item) in items.iter().enumerate() {
+                            self.check(schema, item, &format!("{}/{}", pointer, i), out);
+                        }
+                    }
+                    None => (),
+                }
+            }
+            Value::String(text) => {
+                let length = text.chars().count() as u64;
+                if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                    if length < min {
+                        out.push(
+                            self.violation(
+                                pointer,
+                                format!("expected at least {} characters", min),
+                            ),
+                        );
+                    }
+                }
+                if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                    if length > max {
+                        out.push(
+                            self.violation(pointer, format!("expected at most {} characters", max)),
+                        );
+                    }
+                }
+                if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+                    if Regex::new(pattern).is_ok_and(|x| !x.is_match(text)) {
+                        out.push(self.violation(pointer, format!("does not match '{}'", pattern)));
+                    }
+                }
+            }
+            Value::Number(number) => {
+                let value = number.as_f64().unwrap_or_default();
+                let (format_min, format_max) = schema
+                    .get("format")
+                    .and_then(Value::as_str)
+                    .map_or((None, None), integer_range);
+                let minimum = schema.get("minimum").and_then(Value::as_f64).or(format_min);
+                let maximum = schema.get("maximum").and_then(Value::as_f64).or(format_max);
+                if minimum.is_some_and(|x| value < x) || maximum.is_some_and(|x| value > x) {
+                    let range = match (minimum, maximum) {
+                        (Some(min), Some(max)) => format!("between {} and {}", min, max),
+                        (Some(min), None) => format!("at least {}", min),
+                        (_, max) => format!("at most {}", max.unwrap_or_default()),
+                    };
+                    out.push(self.violation(
+                        pointer,
+                        format!("{} is out of range, expected {}", number, range),
+                    ));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// `anyOf`/`oneOf`, as used for enums. When nothing matches, the errors of the variant
+    /// the message was apparently meant to be are reported instead of a bare "no match".
+    fn check_variants(
+        &self,
+        schemas: &'a [Value],
+        exactly_one: bool,
+        instance: &Value,
+        pointer: &str,
+        out: &mut Vec<SchemaViolation>,
+    ) {
+        let results: Vec<Vec<SchemaViolation>> = schemas
+            .iter()
+            .map(|schema| {
+                let mut violations = vec![];
+                self.check(schema, instance, pointer, &mut violations);
+                violations
+            })
+            .collect();
+        let matches = results.iter().filter(|x| x.is_empty()).count();
+        if matches > 1 && exactly_one {
+            out.push(self.violation(pointer, "matches more than one variant".to_owned()));
+        }
+        if matches > 0 {
+            return;
+        }
+        let intended = schemas.iter().position(|schema| {
+            let required = self.deref(schema).get("required").and_then(Value::as_array);
+            match (instance, required) {
+                (Value::Object(object), Some(required)) => required
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .any(|x| object.contains_key(x)),
+                _ => false,
+            }
+        });
+        match intended {
+            Some(index) => out.extend(results[index].iter().cloned()),
+            None => {
+                let variants: Vec<String> = schemas.iter().flat_map(|x| self.describe(x)).collect();
+                out.push(self.violation(
+                    pointer,
+                    format!(
+                        "does not match any variant, expected one of {}",
+                        variants.join(", ")
+                    ),
+                ));
+            }
+        }
+    }
+
+    /// Short names of the values a variant accepts
+    fn describe(&self, schema: &'a Value) -> Vec<String> {
+        let schema = self.deref(schema);
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            return values.iter().map(|x| x.to_string()).collect();
+        }
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            if let [Value::String(name)] = &required[..] {
+                return vec![format!("{{\"{}\": ...}}", name)];
+            }
+        }
+        match schema.get("type") {
+            Some(Value::String(name)) => vec![name.clone()],
+            _ => vec!["<schema>".to_owned()],
+        }
+    }
+
+    fn deref(&self, schema: &'a Value) -> &'a Value {
+        match schema.get("$ref").and_then(Value::as_str) {
+            Some(reference) => self.resolve(reference).map_or(schema, |x| self.deref(x)),
+            None => schema,
+        }
+    }
+
+    fn resolve(&self, reference: &str) -> Option<&'a Value> {
+        self.root.pointer(reference.strip_prefix('#')?)
+    }
+
+    fn violation(&self, pointer: &str, message: String) -> SchemaViolation {
+        SchemaViolation {
+            pointer: pointer.to_owned(),
+            message,
+        }
+    }
+}
+
+fn is_placeholder(value: &Value) -> bool {
+    value
+        .as_str()
+        .is_some_and(|x| x.len() > 2 && x.starts_with('<') && x.ends_with('>'))
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|x| x.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Bounds of the integer formats schemars emits
+fn integer_range(format: &str) -> (Option<f64>, Option<f64>) {
+    match format {
+        "uint8" => (Some(0.0), Some(u8::MAX as f64)),
+        "uint16" => (Some(0.0), Some(u16::MAX as f64)),
+        "uint32" => (Some(0.0), Some(u32::MAX as f64)),
+        "uint64" | "uint" => (Some(0.0), Some(u64::MAX as f64)),
+        "int8" => (Some(i8::MIN as f64), Some(i8::MAX as f64)),
+        "int16" => (Some(i16::MIN as f64), Some(i16::MAX as f64)),
+        "int32" => (Some(i32::MIN as f64), Some(i32::MAX as f64)),
+        _ => (None, None),
+    }
+}
+
+fn join_values(values: &[Value]) -> String {
+    values
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Escapes a property name for use in a JSON pointer
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn execute_schema() -> Value {
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "ExecuteMsg",
+            "oneOf": [
+                {
+                    "type": "object",
+                    "required": ["change_owner"],
+                    "properties": {
+                        "change_owner": {
+                            "type": "object",
+                            "required": ["addr"],
+                            "properties": { "addr": { "$ref": "#/definitions/Addr" } },
+                            "additionalProperties": false
+                        }
+                    },
+                    "additionalProperties": false
+                },
+                {
+                    "type": "object",
+                    "required": ["set_fees"],
+                    "properties": {
+                        "set_fees": {
+                            "type": "object",
+                            "required": ["fees"],
+                            "properties": {
+                                "fees": {
+                                    "type": "array",
+                                    "items": { "type": "integer", "format": "uint32", "minimum": 0.0 }
+                                },
+                                "memo": { "type": ["string", "null"] }
+                            },
+                            "additionalProperties": false
+                        }
+                    },
+                    "additionalProperties": false
+                },
+                { "type": "string", "enum": ["pause"] }
+            ],
+            "definitions": { "Addr": { "type": "string" } }
+        })
+    }
+
+    #[test]
+    fn accepts_valid_messages() {
+        let schema = execute_schema();
+        for msg in [
+            json!({ "change_owner": { "addr": "archway1abc" } }),
+            json!({ "set_fees": { "fees": [1, 2], "memo": null } }),
+            json!("pause"),
+        ] {
+            assert_eq!(validate(&schema, &msg, false), vec![]);
+        }
+    }
+
+    #[test]
+    fn reports_violations_with_json_pointers() {
+        let schema = execute_schema();
+        let errors = |msg: Value| -> Vec<String> {
+            validate(&schema, &msg, false)
+                .iter()
+                .map(|x| x.to_string())
+                .collect()
+        };
+        assert_eq!(
+            errors(json!({ "set_fees": { "fees": [1, -2, "3"], "note": "x" } })),
+            vec![
+                "/set_fees/fees/1: -2 is out of range, expected between 0 and 4294967295",
+                "/set_fees/fees/2: expected integer, found string",
+                "/set_fees/note: unknown property 'note', expected one of 'fees', 'memo'",
+            ]
+        );
+        assert_eq!(
+            errors(json!({ "change_owner": {} })),
+            vec!["/change_owner: missing required property 'addr'"]
+        );
+        assert_eq!(
+            errors(json!({ "transfer": {} })),
+            vec![
+                r#"(root): does not match any variant, expected one of {"change_owner": ...}, {"set_fees": ...}, "pause""#
+            ]
+        );
+
+        let placeholder = json!({ "change_owner": { "addr": "<address:$_acl>" } });
+        assert_eq!(validate(&schema, &placeholder, true), vec![]);
+    }
+}
@@ -0,0 +1,232 @@
+use std::{collections::BTreeMap, fs, io::Write, path::PathBuf};
+
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bip39::Mnemonic;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::{chains::signer::Signer, error::WarpError};
+
+pub const KEYSTORE_FILENAME: &str = "keys.toml";
+/// Overrides the location of the key store (useful for CI)
+pub const KEYSTORE_ENV: &str = "WARP_KEYSTORE";
+const PBKDF2_ROUNDS: u32 = 210_000;
+
+/// BIP39 mnemonics managed by Warp, each encrypted with its own password-derived key
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct KeyStore {
+    #[serde(default)]
+    pub keys: BTreeMap<String, StoredKey>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct StoredKey {
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+    /// Compressed public keys per HD path, so addresses can be shown without the password
+    #[serde(default)]
+    pub public_keys: BTreeMap<String, String>,
+}
+
+impl KeyStore {
+    pub fn path() -> Result<PathBuf, WarpError> {
+        if let Ok(path) = std::env::var(KEYSTORE_ENV) {
+            return Ok(PathBuf::from(path));
+        }
+        dirs::config_dir()
+            .map(|dir| dir.join("warp").join(KEYSTORE_FILENAME))
+            .ok_or(WarpError::ConfigDirNotFound)
+    }
+
+    pub fn load() -> Result<Self, WarpError> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self) -> Result<(), WarpError> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Written next to the store and renamed over it, so the keys are never readable by
+        // others and a failed write can't leave a truncated store behind
+        let mut temp_name = path.file_name().unwrap_or_default().to_owned();
+        temp_name.push(".tmp");
+        let temp_path = path.with_file_name(temp_name);
+        if temp_path.exists() {
+            fs::remove_file(&temp_path)?;
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&temp_path)?;
+        file.write_all(toml::to_string_pretty(self)?.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.keys.contains_key(name)
+    }
+
+    /// Generates a new 24-word mnemonic and stores it under `name`
+    pub fn generate(&mut self, name: &str, password: &str) -> Result<Mnemonic, WarpError> {
+        let mut entropy = [0u8; 32];
+        OsRng.fill_bytes(&mut entropy);
+        let mnemonic =
+            Mnemonic::from_entropy(&entropy).map_err(|x| WarpError::InvalidKey(x.to_string()))?;
+        self.insert(name, &mnemonic.to_string(), password)?;
+        Ok(mnemonic)
+    }
+
+    pub fn insert(&mut self, name: &str, mnemonic: &str, password: &str) -> Result<(), WarpError> {
+        if self.contains(name) {
+            return Err(WarpError::KeyAlreadyExists(name.to_owned()));
+        }
+        let mnemonic = Mnemonic::parse_normalized(mnemonic.trim())
+            .map_err(|x| WarpError::InvalidKey(x.to_string()))?;
+
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = Self::cipher(password, &salt)
+            .encrypt(&nonce, mnemonic.to_string().as_bytes())
+            .map_err(|_| WarpError::KeyDecryptionFailed(name.to_owned()))?;
+        self.keys.insert(
+            name.to_owned(),
+            StoredKey {
+                salt: STANDARD.encode(salt),
+                nonce: STANDARD.encode(nonce),
+                ciphertext: STANDARD.encode(ciphertext),
+                public_keys: BTreeMap::new(),
+            },
+        );
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<(), WarpError> {
+        self.keys
+            .remove(name)
+            .map(|_| ())
+            .ok_or_else(|| WarpError::KeyNotFound(name.to_owned()))
+    }
+
+    /// Decrypts the mnemonic stored under `name`
+    pub fn mnemonic(&self, name: &str, password: &str) -> Result<String, WarpError> {
+        let key = self
+            .keys
+            .get(name)
+            .ok_or_else(|| WarpError::KeyNotFound(name.to_owned()))?;
+        let decode = |x: &str| {
+            STANDARD
+                .decode(x)
+                .map_err(|_| WarpError::KeyDecryptionFailed(name.to_owned()))
+        };
+        let salt = decode(&key.salt)?;
+        let nonce: [u8; 12] = decode(&key.nonce)?
+            .try_into()
+            .map_err(|_| WarpError::KeyDecryptionFailed(name.to_owned()))?;
+        let plaintext = Self::cipher(password, &salt)
+            .decrypt(&Nonce::from(nonce), decode(&key.ciphertext)?.as_slice())
+            .map_err(|_| WarpError::KeyDecryptionFailed(name.to_owned()))?;
+        Ok(String::from_utf8(plaintext)?)
+    }
+
+    /// Derives the signer of `name` for the given HD path and bech32 prefix and caches its public key
+    pub fn signer(
+        &mut self,
+        name: &str,
+        password: &str,
+        hd_path: &str,
+        prefix: &str,
+    ) -> Result<Signer, WarpError> {
+        let signer = Signer::from_mnemonic(&self.mnemonic(name, password)?, hd_path, prefix)?;
+        if let Some(key) = self.keys.get_mut(name) {
+            key.public_keys
+                .insert(hd_path.to_owned(), STANDARD.encode(signer.public_key()));
+        }
+        Ok(signer)
+    }
+
+    /// Address of `name` if its public key for `hd_path` is already known
+    pub fn cached_address(
+        &self,
+        name: &str,
+        hd_path: &str,
+        prefix: &str,
+    ) -> Result<Option<String>, WarpError> {
+        let public_key = self
+            .keys
+            .get(name)
+            .ok_or_else(|| WarpError::KeyNotFound(name.to_owned()))?
+            .public_keys
+            .get(hd_path);
+        match public_key {
+            Some(public_key) => {
+                let bytes = STANDARD
+                    .decode(public_key)
+                    .map_err(|x| WarpError::InvalidKey(x.to_string()))?;
+                Ok(Some(crate::chains::signer::address(&bytes, prefix)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn cipher(password: &str, salt: &[u8]) -> Aes256Gcm {
+        let mut key = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+        Aes256Gcm::new(&Key::<Aes256Gcm>::from(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn encrypts_and_derives_per_profile() {
+        let mut store = KeyStore::default();
+        store.insert("deployer", MNEMONIC, "hunter2").unwrap();
+        assert!(store.insert("deployer", MNEMONIC, "hunter2").is_err());
+        assert!(!toml::to_string(&store).unwrap().contains("abandon"));
+
+        assert_eq!(store.mnemonic("deployer", "hunter2").unwrap(), MNEMONIC);
+        assert!(matches!(
+            store.mnemonic("deployer", "wrong"),
+            Err(WarpError::KeyDecryptionFailed(_))
+        ));
+
+        let path = "m/44'/118'/0'/0/0";
+        assert_eq!(
+            store.cached_address("deployer", path, "xion").unwrap(),
+            None
+        );
+        let signer = store.signer("deployer", "hunter2", path, "cosmos").unwrap();
+        assert_eq!(
+            signer.address,
+            "cosmos19rl4cm2hmr8afy4kldpxz3fka4jguq0auqdal4"
+        );
+        let xion = store.cached_address("deployer", path, "xion").unwrap();
+        assert!(xion.unwrap().starts_with("xion1"));
+
+        let generated = store.generate("fresh", "pw").unwrap();
+        assert_eq!(generated.word_count(), 24);
+        store.remove("fresh").unwrap();
+        assert!(store.remove("fresh").is_err());
+    }
+}
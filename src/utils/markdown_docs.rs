@@ -0,0 +1,473 @@
+//! Markdown reference pages for `warp docs`: one page per contract with its messages,
+//! their variants and fields, example payloads, the types they use and the addresses
+//! the contract is deployed at.
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map, Value};
+
+use crate::error::WarpError;
+
+use super::contract_schema::{self, ContractSchema, MessageKind};
+
+/// Nesting depth after which example payloads stop expanding recursive types
+const EXAMPLE_DEPTH: usize = 8;
+
+/// An instance of the contract recorded in Deployment.toml
+#[derive(Clone, Debug)]
+pub struct DeployedInstance {
+    /// Deployment.toml network key, e.g. `constantine-3` or `constantine-3/staging`
+    pub network: String,
+    /// Id of the autodeploy step that deployed it
+    pub step: String,
+    pub address: String,
+    pub code_id: Option<String>,
+}
+
+/// Page of the contract `name` (its directory under `contracts/`)
+pub fn generate(
+    name: &str,
+    schema: &ContractSchema,
+    instances: &[DeployedInstance],
+) -> Result<String, WarpError> {
+    let mut messages = vec![];
+    for kind in MessageKind::ALL {
+        if let Some(message) = schema.message_schema(kind)? {
+            messages.push((kind, message));
+        }
+    }
+    let responses = schema.responses()?;
+    let mut page = Page {
+        output: format!(
+            "# `{}`\n\n_Generated by `warp docs` from the contract's JSON schemas, do not edit._\n",
+            name
+        ),
+        definitions: contract_schema::definitions(
+            messages.iter().map(|x| &x.1).chain(responses.values()),
+        ),
+    };
+
+    page.output.push_str("\n## Deployments\n\n");
+    if instances.is_empty() {
+        page.output.push_str("Not deployed yet.\n");
+    } else {
+        page.output
+            .push_str("| Network | Step | Address | Code ID |\n| --- | --- | --- | --- |\n");
+        for instance in instances.iter() {
+            page.output.push_str(&format!(
+                "| {} | `{}` | `{}` | {} |\n",
+                instance.network,
+                instance.step,
+                instance.address,
+                instance.code_id.as_deref().unwrap_or("-")
+            ));
+        }
+    }
+
+    // Response types with a name of their own are listed with the other types
+    for response in responses.values() {
+        if let Some(title) = named_response(response) {
+            page.definitions
+                .entry(title.to_owned())
+                .or_insert_with(|| response.clone());
+        }
+    }
+    for (kind, message) in messages.iter() {
+        let heading = kind.type_name().trim_end_matches("Msg");
+        page.output.push_str(&format!("\n## {}\n", heading));
+        page.description(message);
+        let variants = contract_schema::message_variants(message);
+        if variants.is_empty() {
+            page.fields(message);
+            page.example(message);
+            continue;
+        }
+        for variant in variants.iter() {
+            page.output.push_str(&format!("\n### `{}`\n", variant.name));
+            if let Some(description) = &variant.description {
+                page.output.push_str(&format!("\n{}\n", description.trim()));
+            }
+            let example = match &variant.fields {
+                Some(fields) => {
+                    page.fields(fields);
+                    json!({ &variant.name: page.example_value(fields, 0) })
+                }
+                None => json!(variant.name),
+            };
+            if *kind == MessageKind::Query {
+                if let Some(response) = responses.get(&variant.name) {
+                    let returns = match named_response(response) {
+                        Some(title) => type_link(title),
+                        None => page.type_name(response),
+                    };
+                    page.output.push_str(&format!("\nReturns {}.\n", returns));
+                }
+            }
+            page.output
+                .push_str(&format!("\n```json\n{:#}\n```\n", example));
+        }
+    }
+
+    if !page.definitions.is_empty() {
+        page.output.push_str("\n## Types\n");
+        for (name, definition) in page.definitions.clone().iter() {
+            page.output.push_str(&format!("\n### `{}`\n", name));
+            page.description(definition);
+            let variants = contract_schema::message_variants(definition);
+            if definition.get("properties").is_some() {
+                page.fields(definition);
+            } else if !variants.is_empty() {
+                page.output.push_str("\nOne of:\n\n");
+                for variant in variants.iter() {
+                    let shape = match &variant.fields {
+                        Some(fields) => format!(
+                            "`{{\"{}\": {{...}}}}` with {}",
+                            variant.name,
+                            page.field_list(fields)
+                        ),
+                        None => format!("`\"{}\"`", variant.name),
+                    };
+                    page.output.push_str(&format!("- {}\n", shape));
+                }
+            } else {
+                let type_name = page.type_name(definition);
+                page.output.push_str(&format!("\nType: {}.\n", type_name));
+            }
+        }
+    }
+    Ok(page.output)
+}
+
+/// `README.md` of the docs directory, linking the pages of `contracts`
+pub fn index(contracts: &[String]) -> String {
+    let mut output = "# Contracts\n\n_Generated by `warp docs`, do not edit._\n\n".to_owned();
+    for name in contracts.iter() {
+        output.push_str(&format!("- [`{0}`](./{0}.md)\n", name));
+    }
+    output
+}
+
+struct Page {
+    output: String,
+    /// Named types of the contract, listed under "Types"
+    definitions: BTreeMap<String, Value>,
+}
+
+impl Page {
+    fn description(&mut self, schema: &Value) {
+        if let Some(description) = schema.get("description").and_then(Value::as_str) {
+            self.output.push_str(&format!("\n{}\n", description.trim()));
+        }
+    }
+
+    /// Table of the properties of an object schema
+    fn fields(&mut self, schema: &Value) {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let Some(properties) = properties.filter(|x| !x.is_empty()) else {
+            self.output.push_str("\nNo fields.\n");
+            return;
+        };
+        let required = required(schema);
+        self.output
+            .push_str("\n| Field | Type | Required | Description |\n| --- | --- | --- | --- |\n");
+        for (field, field_schema) in properties.iter() {
+            let description = field_schema
+                .get("description")
+                .and_then(Value::as_str)
+                .map(table_cell)
+                .unwrap_or_default();
+            let row = format!(
+                "| `{}` | {} | {} | {} |\n",
+                field,
+                self.type_name(field_schema),
+                if required.contains(&field.as_str()) {
+                    "yes"
+                } else {
+                    "no"
+                },
+                description
+            );
+            self.output.push_str(&row);
+        }
+    }
+
+    /// Inline list of the fields of a variant, for the "Types" section
+    fn field_list(&self, schema: &Value) -> String {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        match properties.filter(|x| !x.is_empty()) {
+            Some(properties) => properties
+                .iter()
+                .map(|(field, x)| format!("`{}`: {}", field, self.type_name(x)))
+                .collect::<Vec<_>>()
+                .join(", "),
+            None => "no fields".to_owned(),
+        }
+    }
+
+    fn example(&mut self, schema: &Value) {
+        let example = self.example_value(schema, 0);
+        self.output
+            .push_str(&format!("\n```json\n{:#}\n```\n", example));
+    }
+
+    /// Markdown description of a type, with links to the named types
+    fn type_name(&self, schema: &Value) -> String {
+        let (schema, nullable) = contract_schema::nullable(schema);
+        let name = self.non_null_type_name(schema);
+        match nullable {
+            true => format!("{} or `null`", name),
+            false => name,
+        }
+    }
+
+    fn non_null_type_name(&self, schema: &Value) -> String {
+        let Value::Object(object) = schema else {
+            return "any value".to_owned();
+        };
+        if let Some(reference) = object.get("$ref").and_then(Value::as_str) {
+            return type_link(reference.rsplit('/').next().unwrap_or(reference));
+        }
+        if let Some(Value::Array(all)) = object.get("allOf") {
+            return join(all.iter().map(|x| self.type_name(x)), " and ");
+        }
+        for key in ["oneOf", "anyOf"] {
+            if let Some(Value::Array(any)) = object.get(key) {
+                return join(any.iter().map(|x| self.type_name(x)), " or ");
+            }
+        }
+        if let Some(Value::Array(values)) = object.get("enum") {
+            return join(values.iter().map(|x| format!("`{}`", x)), " or ");
+        }
+        if let Some(value) = object.get("const") {
+            return format!("`{}`", value);
+        }
+        match object.get("type") {
+            Some(Value::Array(types)) => join(
+                types
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .filter(|x| *x != "null")
+                    .map(|x| self.single_type(x, object)),
+                " or ",
+            ),
+            Some(Value::String(x)) => self.single_type(x, object),
+            _ if object.contains_key("properties") => "`object`".to_owned(),
+            _ => "any value".to_owned(),
+        }
+    }
+
+    fn single_type(&self, type_name: &str, object: &Map<String, Value>) -> String {
+        match type_name {
+            "array" => match object.get("items") {
+                Some(Value::Array(items)) => format!(
+                    "tuple [{}]",
+                    join(items.iter().map(|x| self.type_name(x)), ", ")
+                ),
+                Some(items) => format!("array of {}", self.type_name(items)),
+                None => "`array`".to_owned(),
+            },
+            "object" => match object.get("additionalProperties") {
+                Some(values @ Value::Object(_)) if !object.contains_key("properties") => {
+                    format!("map of {}", self.type_name(values))
+                }
+                _ => "`object`".to_owned(),
+            },
+            _ => match object.get("format").and_then(Value::as_str) {
+                Some(format) => format!("`{}` (`{}`)", type_name, format),
+                None => format!("`{}`", type_name),
+            },
+        }
+    }
+
+    /// Example payload matching `schema`, using the first alternative of every choice
+    fn example_value(&self, schema: &Value, depth: usize) -> Value {
+        if depth > EXAMPLE_DEPTH {
+            return Value::Null;
+        }
+        let (schema, _) = contract_schema::nullable(schema);
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let name = reference.rsplit('/').next().unwrap_or(reference);
+            return match (string_example(name), self.definitions.get(name)) {
+                (Some(example), _) => Value::from(example),
+                (None, Some(definition)) => self.example_value(definition, depth + 1),
+                (None, None) => Value::Null,
+            };
+        }
+        if let Some(variant) = contract_schema::message_variants(schema).first() {
+            return match &variant.fields {
+                Some(fields) => json!({ &variant.name: self.example_value(fields, depth + 1) }),
+                None => json!(variant.name),
+            };
+        }
+        for key in ["allOf", "oneOf", "anyOf"] {
+            if let Some(first) = schema
+                .get(key)
+                .and_then(Value::as_array)
+                .and_then(|x| x.first())
+            {
+                return self.example_value(first, depth + 1);
+            }
+        }
+        if let Some(first) = schema
+            .get("enum")
+            .and_then(Value::as_array)
+            .and_then(|x| x.first())
+        {
+            return first.clone();
+        }
+        if let Some(value) = schema.get("const") {
+            return value.clone();
+        }
+        let type_name = match schema.get("type") {
+            Some(Value::String(x)) => x.as_str(),
+            Some(Value::Array(types)) => types
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|x| *x != "null")
+                .unwrap_or("null"),
+            _ if schema.get("properties").is_some() => "object",
+            _ => return Value::Null,
+        };
+        match type_name {
+            "string" => Value::from("string"),
+            "integer" => Value::from(0),
+            "number" => json!(0.0),
+            "boolean" => Value::from(false),
+            "array" => match schema.get("items") {
+                Some(Value::Array(items)) => items
+                    .iter()
+                    .map(|x| self.example_value(x, depth + 1))
+                    .collect(),
+                Some(items) => Value::Array(vec![self.example_value(items, depth + 1)]),
+                None => json!([]),
+            },
+            "object" => {
+                let properties = schema.get("properties").and_then(Value::as_object);
+                let fields: Map<String, Value> = properties
+                    .into_iter()
+                    .flatten()
+                    .map(|(name, x)| (name.clone(), self.example_value(x, depth + 1)))
+                    .collect();
+                Value::Object(fields)
+            }
+            _ => Value::Null,
+        }
+    }
+}
+
+/// Example values of the `cosmwasm_std` types serialized as strings
+fn string_example(name: &str) -> Option<&'static str> {
+    match name {
+        "Uint64" | "Uint128" | "Uint256" | "Uint512" | "Int64" | "Int128" | "Int256" | "Int512" => {
+            Some("1000")
+        }
+        "Decimal" | "Decimal256" | "SignedDecimal" | "SignedDecimal256" => Some("0.5"),
+        "Timestamp" => Some("1700000000000000000"),
+        "Binary" => Some("e30="),
+        _ => None,
+    }
+}
+
+/// Title of a response schema that describes a named type rather than a primitive or
+/// a generic wrapper such as `Array_of_Coin`
+fn named_response(schema: &Value) -> Option<&str> {
+    let title = schema.get("title").and_then(Value::as_str)?;
+    let named =
+        schema.get("properties").is_some() || !contract_schema::message_variants(schema).is_empty();
+    (named && title.chars().all(|x| x.is_ascii_alphanumeric())).then_some(title)
+}
+
+fn type_link(name: &str) -> String {
+    format!("[`{}`](#{})", name, name.to_lowercase())
+}
+
+fn required(schema: &Value) -> Vec<&str> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect()
+}
+
+fn join(parts: impl Iterator<Item = String>, separator: &str) -> String {
+    parts.collect::<Vec<_>>().join(separator)
+}
+
+/// Text that fits in a single Markdown table cell
+fn table_cell(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn documents_messages_types_and_deployments() {
+        let root = std::env::temp_dir().join(format!("warp-docs-{}", std::process::id()));
+        let contract = root.join("contracts").join("token");
+        fs::create_dir_all(contract.join("schema")).unwrap();
+        fs::write(
+            contract.join("schema").join("token.json"),
+            r##"{
+                "contract_name": "token",
+                "execute": {
+                    "title": "ExecuteMsg",
+                    "oneOf": [{
+                        "description": "Sends tokens | to a recipient",
+                        "type": "object",
+                        "required": ["transfer"],
+                        "properties": { "transfer": {
+                            "type": "object",
+                            "required": ["amount"],
+                            "properties": {
+                                "amount": { "$ref": "#/definitions/Uint128" },
+                                "memo": { "description": "Free | text", "type": ["string", "null"] }
+                            }
+                        } }
+                    }],
+                    "definitions": { "Uint128": { "type": "string" } }
+                },
+                "query": { "title": "QueryMsg", "oneOf": [{ "type": "string", "enum": ["supply"] }] },
+                "responses": { "supply": {
+                    "title": "SupplyResponse",
+                    "type": "object",
+                    "required": ["total"],
+                    "properties": { "total": { "$ref": "#/definitions/Uint128" } },
+                    "definitions": { "Uint128": { "type": "string" } }
+                } }
+            }"##,
+        )
+        .unwrap();
+        let schema = ContractSchema::load_dir(&contract, false).unwrap().unwrap();
+        let page = generate(
+            "token",
+            &schema,
+            &[DeployedInstance {
+                network: "constantine-3".to_owned(),
+                step: "$_token".to_owned(),
+                address: "archway1token".to_owned(),
+                code_id: Some("7".to_owned()),
+            }],
+        )
+        .unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(page.contains("| constantine-3 | `$_token` | `archway1token` | 7 |\n"));
+        assert!(page.contains("### `transfer`\n\nSends tokens | to a recipient\n"));
+        assert!(page.contains("| `memo` | `string` or `null` | no | Free \\| text |\n"));
+        assert!(page.contains(
+            "```json\n{\n  \"transfer\": {\n    \"amount\": \"1000\",\n    \"memo\": \"string\"\n  }\n}\n```"
+        ));
+        assert!(page.contains("```json\n\"supply\"\n```"));
+        assert!(page.contains("Returns [`SupplyResponse`](#supplyresponse)."));
+        assert!(page.contains("### `SupplyResponse`\n"));
+    }
+}
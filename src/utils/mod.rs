@@ -1,5 +1,18 @@
 pub mod command_util;
+pub mod contract_schema;
+pub mod deployment_export;
+pub mod deployment_graph;
+pub mod deployment_journal;
+pub mod deployment_plan;
 pub mod deployment_result;
 pub mod deployment_task;
-pub mod project_config;
 pub mod file_util;
+pub mod json_schema;
+pub mod keystore;
+pub mod markdown_docs;
+pub mod project_config;
+pub mod rust_codegen;
+pub mod schema_diff;
+pub mod schema_index;
+pub mod template;
+pub mod ts_codegen;
@@ -1,11 +1,15 @@
-use serde::{Deserialize, Serialize};
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+use std::collections::BTreeMap;
 use std::fs::{self, File};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::error::WarpError;
+use crate::utils::{deployment_export::ExportTarget, deployment_graph::ACCOUNT_PLACEHOLDER};
 
 pub const CONFIG_FILENAME: &str = "Warp.toml";
+/// Value of a step's `admin` that instantiates the contract without an admin
+pub const NO_ADMIN: &str = "none";
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ProjectConfig {
@@ -13,6 +17,14 @@ pub struct ProjectConfig {
     pub tooling: Tooling,
     pub tests: TestConfig,
     pub autodeploy: AutoDeploy,
+    /// Named sets of contracts that are deployed and tracked separately, selected with `--env`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub environments: BTreeMap<String, Environment>,
+    #[serde(default, skip_serializing_if = "CodegenConfig::is_default")]
+    pub codegen: CodegenConfig,
+    /// Environment applied by [`ProjectConfig::with_environment`]
+    #[serde(skip)]
+    pub environment: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -21,8 +33,104 @@ pub struct Network {
     pub profile: String,
     pub chain_id: String,
     pub rpc_url: String,
+    /// LCD (REST) endpoint used for queries that don't need the chain daemon
+    pub lcd_url: Option<String>,
     pub denom: String,
     pub gas_prices: Option<String>,
+    /// Who signs and broadcasts transactions: the chain daemon or Warp itself
+    #[serde(default)]
+    pub tx_backend: TxBackend,
+    /// How long to wait for broadcast transactions to be included in a block
+    #[serde(default)]
+    pub confirmation: TxConfirmation,
+    /// Gas limit and gas price strategy for every transaction on this network
+    #[serde(default)]
+    pub fees: FeeConfig,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum TxBackend {
+    /// Pipe transactions through the chain daemon (`archwayd tx wasm ...`)
+    #[default]
+    Daemon,
+    /// Sign with SIGN_MODE_DIRECT in-process and broadcast through the LCD endpoint
+    Native,
+}
+
+/// `[network.fees]`, or the `fees` table of a deploy step. Unset fields fall back
+/// to the network settings and then to the chain profile's gas defaults.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+pub struct FeeConfig {
+    /// `simulate` the transaction or use a `fixed` gas limit
+    pub gas: Option<GasStrategy>,
+    /// Gas limit used by the `fixed` strategy
+    pub gas_limit: Option<u64>,
+    /// Multiplier applied to simulated gas (except code uploads)
+    pub gas_adjustment: Option<f64>,
+    /// Multiplier applied to simulated gas for code uploads
+    pub store_gas_adjustment: Option<f64>,
+    /// Ask the chain for the current gas price (`estimate`) or use `gas_prices` (`static`)
+    pub price: Option<PriceStrategy>,
+    /// Static gas price, e.g. `0.025uxion`
+    pub gas_prices: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum GasStrategy {
+    #[default]
+    Simulate,
+    Fixed,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceStrategy {
+    /// Chain-specific estimation, such as Archway's rewards module
+    Estimate,
+    Static,
+}
+
+impl FeeConfig {
+    /// `overrides` layered on top of `self`
+    pub fn merged(&self, overrides: &FeeConfig) -> FeeConfig {
+        FeeConfig {
+            gas: overrides.gas.clone().or_else(|| self.gas.clone()),
+            gas_limit: overrides.gas_limit.or(self.gas_limit),
+            gas_adjustment: overrides.gas_adjustment.or(self.gas_adjustment),
+            store_gas_adjustment: overrides.store_gas_adjustment.or(self.store_gas_adjustment),
+            price: overrides.price.clone().or_else(|| self.price.clone()),
+            gas_prices: overrides
+                .gas_prices
+                .clone()
+                .or_else(|| self.gas_prices.clone()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct TxConfirmation {
+    /// Give up waiting after this many seconds
+    pub timeout_secs: u64,
+    /// Delay before the first lookup, in milliseconds
+    pub poll_interval_ms: u64,
+    /// Upper bound for the delay between lookups, in milliseconds
+    pub max_poll_interval_ms: u64,
+    /// Factor applied to the delay after every unsuccessful lookup
+    pub backoff: f64,
+}
+
+impl Default for TxConfirmation {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 60,
+            poll_interval_ms: 500,
+            max_poll_interval_ms: 5000,
+            backoff: 1.5,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -38,22 +146,221 @@ pub struct TestConfig {
     pub persist_image: bool,
 }
 
+/// `[environments.<name>]` overrides of the autodeploy settings
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct Environment {
+    /// Deployment account, replacing `autodeploy.account_id`
+    pub account_id: Option<String>,
+    /// Prepended to the label of every contract
+    pub label_prefix: Option<String>,
+    /// Admins by step id, replacing the steps' `admin`
+    pub admins: BTreeMap<String, String>,
+    /// Init messages by step id, replacing the steps' `init_msg`
+    #[serde(serialize_with = "toml::ser::tables_last")]
+    pub init_msgs: BTreeMap<String, StepMessage>,
+}
+
+/// `[codegen]`: where `warp codegen` and `warp docs` write their output
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct CodegenConfig {
+    /// Output directory of `warp codegen ts`, relative to the project root
+    pub ts_out: PathBuf,
+    /// Output directory of `warp docs`, relative to the project root
+    pub docs_out: PathBuf,
+}
+
+impl Default for CodegenConfig {
+    fn default() -> Self {
+        Self {
+            ts_out: PathBuf::from("tests/src/generated"),
+            docs_out: PathBuf::from("docs/contracts"),
+        }
+    }
+}
+
+impl CodegenConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AutoDeploy {
     pub account_id: String,
     pub make_labels_unique: bool,
     pub steps: Vec<AutoDeployStep>,
+    /// Files rewritten with the deployed addresses after every successful `warp deploy`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub export: Vec<ExportTarget>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Deserialize, Clone, Debug)]
 pub struct AutoDeployStep {
     pub id: String,
     pub contract: String,
     pub label: String,
     pub store_only: bool,
-    pub migrate_msg: Option<String>,
-    pub init_msg: String,
+    /// Admin of the contract: an address, a `$name` placeholder or template, or `none`
+    /// for an immutable contract. Defaults to the deployer.
+    pub admin: Option<String>,
+    pub migrate_msg: Option<StepMessage>,
+    /// Migrate message sent by `warp rollback`, defaults to `migrate_msg`
+    pub rollback_msg: Option<StepMessage>,
+    pub init_msg: StepMessage,
     pub coins: Option<String>,
+    /// Overrides `[network.fees]` for the transactions of this step
+    pub fees: Option<FeeConfig>,
+    /// Contract calls made once every step has been deployed
+    #[serde(default)]
+    pub after: Vec<AfterAction>,
+}
+
+impl AutoDeployStep {
+    /// Name used by the `$<name>` (address) and `#<name>` (code ID) placeholders,
+    /// i.e. the step id without a leading sigil
+    pub fn reference(&self) -> &str {
+        self.id.trim_start_matches(['$', '#'])
+    }
+
+    /// Admin as a JSON string whose placeholders still need rendering, `None` when the
+    /// contract gets no admin
+    pub fn admin_json(&self) -> Option<String> {
+        match self.admin.as_deref() {
+            Some(NO_ADMIN) => None,
+            Some(admin) => Some(serde_json::Value::from(admin).to_string()),
+            None => Some(format!("\"${}\"", ACCOUNT_PLACEHOLDER)),
+        }
+    }
+
+    /// Migrate message as JSON text, `{}` when the step has none
+    pub fn migrate_json(&self, project_root: &Path) -> Result<String, WarpError> {
+        match &self.migrate_msg {
+            Some(msg) => msg.to_json(project_root),
+            None => Ok("{}".to_owned()),
+        }
+    }
+}
+
+/// `[[autodeploy.steps.after]]`: an execute message sent once all contracts exist, e.g.
+/// to tell a contract about another one that was instantiated after it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AfterAction {
+    /// Identifies the action in Deployment.toml, defaults to its position in the list
+    pub id: Option<String>,
+    /// Id of the step whose contract is called, defaults to the step the action belongs to
+    pub contract: Option<String>,
+    /// Run on every `warp deploy` instead of once per instantiation
+    #[serde(default)]
+    pub always: bool,
+    pub msg: StepMessage,
+}
+
+impl AfterAction {
+    /// Key under which the action is recorded, `index` being its position in the list
+    pub fn key(&self, index: usize) -> String {
+        self.id.clone().unwrap_or_else(|| index.to_string())
+    }
+
+    /// Step whose contract the action calls, `owner` being the step it's declared in.
+    /// `contract` may be given with or without its `$` sigil.
+    pub fn target<'a>(
+        &self,
+        owner: &'a AutoDeployStep,
+        steps: &'a [AutoDeployStep],
+    ) -> Option<&'a AutoDeployStep> {
+        match &self.contract {
+            Some(contract) => steps
+                .iter()
+                .find(|x| x.id == *contract || x.reference() == contract.trim_start_matches('$')),
+            None => Some(owner),
+        }
+    }
+}
+
+// Written by hand because TOML needs all plain values of a step before its tables
+impl Serialize for AutoDeployStep {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let messages = [
+            ("migrate_msg", self.migrate_msg.as_ref()),
+            ("rollback_msg", self.rollback_msg.as_ref()),
+            ("init_msg", Some(&self.init_msg)),
+        ];
+        let mut state = serializer.serialize_struct("AutoDeployStep", 11)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("contract", &self.contract)?;
+        state.serialize_field("label", &self.label)?;
+        state.serialize_field("store_only", &self.store_only)?;
+        if self.admin.is_some() {
+            state.serialize_field("admin", &self.admin)?;
+        }
+        for tables in [false, true] {
+            for (key, msg) in messages.iter() {
+                if let Some(msg) = msg.filter(|x| matches!(x, StepMessage::Table(_)) == tables) {
+                    state.serialize_field(key, msg)?;
+                }
+            }
+            if !tables {
+                state.serialize_field("coins", &self.coins)?;
+            }
+        }
+        state.serialize_field("fees", &self.fees)?;
+        if !self.after.is_empty() {
+            state.serialize_field("after", &self.after)?;
+        }
+        state.end()
+    }
+}
+
+/// Init, migrate or rollback message of a step: JSON text, a path to a `.json` file
+/// (relative to the project root), or a TOML table
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum StepMessage {
+    Text(String),
+    Table(toml::value::Table),
+}
+
+impl StepMessage {
+    /// The message as JSON text, ready for templating. Tables and files are converted to
+    /// compact JSON; files that aren't valid JSON yet (e.g. because of unquoted
+    /// templates) and inline text are used as written.
+    pub fn to_json(&self, project_root: &Path) -> Result<String, WarpError> {
+        match self {
+            StepMessage::Text(path) if path.trim_end().ends_with(".json") => {
+                let content = fs::read_to_string(project_root.join(path.trim()))?;
+                Ok(serde_json::from_str::<serde_json::Value>(&content)
+                    .map(|x| x.to_string())
+                    .unwrap_or(content))
+            }
+            StepMessage::Text(text) => Ok(text.clone()),
+            StepMessage::Table(table) => {
+                Ok(toml_to_json(toml::Value::Table(table.clone())).to_string())
+            }
+        }
+    }
+}
+
+impl From<&str> for StepMessage {
+    fn from(text: &str) -> Self {
+        StepMessage::Text(text.to_owned())
+    }
+}
+
+fn toml_to_json(value: toml::Value) -> serde_json::Value {
+    match value {
+        toml::Value::String(x) => x.into(),
+        toml::Value::Integer(x) => x.into(),
+        toml::Value::Float(x) => x.into(),
+        toml::Value::Boolean(x) => x.into(),
+        toml::Value::Datetime(x) => x.to_string().into(),
+        toml::Value::Array(x) => x.into_iter().map(toml_to_json).collect(),
+        toml::Value::Table(x) => x
+            .into_iter()
+            .map(|(key, value)| (key, toml_to_json(value)))
+            .collect(),
+    }
 }
 
 impl ProjectConfig {
@@ -63,8 +370,12 @@ impl ProjectConfig {
                 profile: String::new(),
                 chain_id: String::new(),
                 rpc_url: String::new(),
+                lcd_url: None,
                 denom: String::new(),
                 gas_prices: None,
+                tx_backend: TxBackend::Daemon,
+                confirmation: TxConfirmation::default(),
+                fees: FeeConfig::default(),
             },
             tooling: Tooling {
                 optimizer_backend: String::new(),
@@ -78,7 +389,11 @@ impl ProjectConfig {
                 account_id: String::new(),
                 make_labels_unique: false,
                 steps: vec![],
+                export: vec![],
             },
+            environments: BTreeMap::new(),
+            codegen: CodegenConfig::default(),
+            environment: None,
         }
     }
     pub fn generate_and_save(path: PathBuf, network: Network) -> Result<(), WarpError> {
@@ -103,7 +418,11 @@ impl ProjectConfig {
                 account_id: "dev".to_owned(),
                 make_labels_unique: true,
                 steps: vec![],
+                export: vec![],
             },
+            environments: BTreeMap::new(),
+            codegen: CodegenConfig::default(),
+            environment: None,
         };
         println!(
             "Project dir: {}",
@@ -150,9 +469,163 @@ impl ProjectConfig {
         }
     }
 
+    /// Applies the overrides of the named environment. Without a name the config is
+    /// returned unchanged.
+    pub fn with_environment(mut self, name: Option<&str>) -> Result<Self, WarpError> {
+        let Some(name) = name else {
+            return Ok(self);
+        };
+        let environment = self
+            .environments
+            .get(name)
+            .cloned()
+            .ok_or_else(|| WarpError::UnknownEnvironment(name.to_owned()))?;
+        if let Some(account_id) = environment.account_id {
+            self.autodeploy.account_id = account_id;
+        }
+        for (id, init_msg) in environment.init_msgs {
+            let step = self
+                .autodeploy
+                .steps
+                .iter_mut()
+                .find(|x| x.id == id)
+                .ok_or(WarpError::ContractIdNotFound(id))?;
+            step.init_msg = init_msg;
+        }
+        for (id, admin) in environment.admins {
+            let step = self
+                .autodeploy
+                .steps
+                .iter_mut()
+                .find(|x| x.id == id)
+                .ok_or(WarpError::ContractIdNotFound(id))?;
+            step.admin = Some(admin);
+        }
+        if let Some(prefix) = environment.label_prefix {
+            for step in self.autodeploy.steps.iter_mut() {
+                step.label = format!("{}{}", prefix, step.label);
+            }
+        }
+        self.environment = Some(name.to_owned());
+        Ok(self)
+    }
+
+    /// Key of the contracts in Deployment.toml: the chain ID, followed by `/<environment>`
+    /// when one is selected
+    pub fn deployment_key(&self) -> String {
+        match &self.environment {
+            Some(environment) => format!("{}/{}", self.network.chain_id, environment),
+            None => self.network.chain_id.clone(),
+        }
+    }
+
+    /// Copy of the config with the step's `fees` layered over `[network.fees]`
+    pub fn for_step(&self, step: &AutoDeployStep) -> ProjectConfig {
+        let mut config = self.clone();
+        if let Some(fees) = &step.fees {
+            config.network.fees = config.network.fees.merged(fees);
+        }
+        config
+    }
+
     pub fn save_project_config(&self) -> Result<(), WarpError> {
         let toml_path = Self::find_project_root()?.join(CONFIG_FILENAME);
         std::fs::write(toml_path, toml::to_string_pretty(self)?)?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn environments_override_autodeploy_settings() {
+        let mut config = ProjectConfig::empty();
+        config.network.chain_id = "constantine-3".to_owned();
+        config.autodeploy.account_id = "dev".to_owned();
+        config.autodeploy.steps.push(AutoDeployStep {
+            id: "$_acl".to_owned(),
+            contract: "artifacts/acl.wasm".to_owned(),
+            label: "ACL".to_owned(),
+            store_only: false,
+            admin: None,
+            migrate_msg: None,
+            rollback_msg: None,
+            init_msg: "{}".into(),
+            coins: None,
+            fees: None,
+            after: vec![],
+        });
+        config.environments = toml::from_str(
+            r#"
+            [staging]
+            account_id = "stage"
+            label_prefix = "staging-"
+            init_msgs = { "$_acl" = '{ "open": true }' }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.deployment_key(), "constantine-3");
+
+        let staging = config.clone().with_environment(Some("staging")).unwrap();
+        assert_eq!(staging.deployment_key(), "constantine-3/staging");
+        assert_eq!(staging.autodeploy.account_id, "stage");
+        assert_eq!(staging.autodeploy.steps[0].label, "staging-ACL");
+        assert_eq!(
+            staging.autodeploy.steps[0].init_msg,
+            r#"{ "open": true }"#.into()
+        );
+
+        assert!(config.clone().with_environment(Some("prod")).is_err());
+        assert_eq!(
+            config.with_environment(None).unwrap().deployment_key(),
+            "constantine-3"
+        );
+    }
+
+    #[test]
+    fn messages_can_be_tables_or_json_files() {
+        let steps: AutoDeploy = toml::from_str(
+            r#"
+            account_id = "dev"
+            make_labels_unique = false
+
+            [[steps]]
+            id = "$_factory"
+            contract = "artifacts/factory.wasm"
+            label = "Factory"
+            store_only = false
+            migrate_msg = "migrate.json"
+            init_msg = { acl = "$_acl", code_id = "{{ steps.pair.code_id | number }}", fees = [1, 2.5] }
+            coins = "1uconst"
+
+            [steps.rollback_msg]
+            restore = true
+            "#,
+        )
+        .unwrap();
+        let root = std::env::temp_dir().join(format!("warp-messages-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("migrate.json"), "{\n  \"version\": 2\n}").unwrap();
+
+        let step = &steps.steps[0];
+        assert_eq!(
+            step.init_msg.to_json(&root).unwrap(),
+            r#"{"acl":"$_acl","code_id":"{{ steps.pair.code_id | number }}","fees":[1,2.5]}"#
+        );
+        assert_eq!(step.migrate_json(&root).unwrap(), r#"{"version":2}"#);
+        assert_eq!(
+            step.rollback_msg.as_ref().unwrap().to_json(&root).unwrap(),
+            r#"{"restore":true}"#
+        );
+
+        // Saving Warp.toml keeps the tables
+        let saved = toml::to_string_pretty(&steps).unwrap();
+        let reparsed: AutoDeploy = toml::from_str(&saved).unwrap();
+        assert_eq!(reparsed.steps[0].init_msg, step.init_msg);
+        assert_eq!(reparsed.steps[0].rollback_msg, step.rollback_msg);
+        assert_eq!(reparsed.steps[0].coins, step.coins);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
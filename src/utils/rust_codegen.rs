@@ -0,0 +1,523 @@
+//! Typed Rust interface modules for the shared package. The message types themselves
+//! live in `packages/shared/src/<contract>/msg.rs`; the generated `interface.rs` next to
+//! it adds a `<Contract>Contract` address wrapper with `WasmMsg` builders per `ExecuteMsg`
+//! variant, `query_*` helpers returning the `#[returns]` types, and an `InstantiateMsg`
+//! helper. Rust types are recovered from the JSON schemas, so variants whose fields
+//! can't be typed only get the generic `execute` builder.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+
+use crate::error::WarpError;
+
+use super::{
+    contract_schema::{self, ContractSchema, MessageKind, MessageVariant},
+    ts_codegen::pascal_case,
+};
+
+const KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+    "where", "while", "yield",
+];
+
+/// Builder names taken by the wrapper itself
+const RESERVED: [&str; 4] = ["new", "addr", "execute", "instantiate"];
+
+/// `cosmwasm_std` types schemas refer to. `msg.rs` imports them privately, so the
+/// interface imports them itself instead of through `super::msg::*`.
+const STD_TYPES: [&str; 19] = [
+    "Addr",
+    "Binary",
+    "Checksum",
+    "Coin",
+    "Decimal",
+    "Decimal256",
+    "Empty",
+    "HexBinary",
+    "Int128",
+    "Int256",
+    "Int512",
+    "Int64",
+    "SignedDecimal",
+    "SignedDecimal256",
+    "Timestamp",
+    "Uint128",
+    "Uint256",
+    "Uint512",
+    "Uint64",
+];
+
+/// Rewrites `packages/shared/src/<name>/interface.rs` and declares it in the module's
+/// `mod.rs`. `None` when the shared package has no module for the contract.
+pub fn write(
+    project_root: &Path,
+    name: &str,
+    schema: &ContractSchema,
+) -> Result<Option<PathBuf>, WarpError> {
+    let module_dir = project_root
+        .join("packages")
+        .join("shared")
+        .join("src")
+        .join(name);
+    if !module_dir.join("msg.rs").exists() {
+        return Ok(None);
+    }
+    let path = module_dir.join("interface.rs");
+    let serializer = binary_serializer(project_root);
+    fs::write(&path, generate(name, schema, serializer)?)?;
+    let mod_path = module_dir.join("mod.rs");
+    let declarations = fs::read_to_string(&mod_path).unwrap_or_default();
+    if !declarations
+        .lines()
+        .any(|x| x.trim() == "pub mod interface;")
+    {
+        let separator = match declarations.is_empty() || declarations.ends_with('\n') {
+            true => "",
+            false => "\n",
+        };
+        fs::write(
+            &mod_path,
+            format!("{}{}pub mod interface;\n", declarations, separator),
+        )?;
+    }
+    Ok(Some(path))
+}
+
+/// `to_json_binary`, or `to_binary` when the workspace locks a cosmwasm-std older
+/// than 1.5, which doesn't have it yet
+pub fn binary_serializer(project_root: &Path) -> &'static str {
+    let lock = fs::read_to_string(project_root.join("Cargo.lock")).unwrap_or_default();
+    let lock: toml::Value = toml::from_str(&lock).unwrap_or(toml::Value::Boolean(false));
+    let packages = lock.get("package").and_then(toml::Value::as_array);
+    let legacy = packages.into_iter().flatten().any(|x| {
+        let version = x.get("version").and_then(toml::Value::as_str);
+        x.get("name").and_then(toml::Value::as_str) == Some("cosmwasm-std")
+            && version.is_some_and(|x| {
+                let mut parts = x.split('.').map(|x| x.parse::<u64>().unwrap_or(0));
+                (parts.next().unwrap_or(0), parts.next().unwrap_or(0)) < (1, 5)
+            })
+    });
+    match legacy {
+        true => "to_binary",
+        false => "to_json_binary",
+    }
+}
+
+/// Source of the interface module of the contract `name`, serializing messages with
+/// `serializer` (see [`binary_serializer`])
+pub fn generate(
+    name: &str,
+    schema: &ContractSchema,
+    serializer: &str,
+) -> Result<String, WarpError> {
+    let wrapper = format!("{}Contract", pascal_case(name));
+    let execute = schema.message_schema(MessageKind::Execute)?;
+    let query = schema.message_schema(MessageKind::Query)?;
+    let has_instantiate = schema.message_schema(MessageKind::Instantiate)?.is_some();
+    let responses = schema.responses()?;
+
+    let mut imports = BTreeSet::from(["Addr", "StdResult"]);
+    if has_instantiate || execute.is_some() {
+        imports.extend(["Coin", "CosmosMsg", "WasmMsg", serializer]);
+    }
+    if query.is_some() {
+        imports.extend(["CustomQuery", "QuerierWrapper"]);
+    }
+    // Types named in builder parameters and query results
+    let mut signatures = String::new();
+
+    let mut body = String::new();
+    if has_instantiate {
+        body.push_str(&format!(
+            "
+    /// `WasmMsg::Instantiate` of `code_id` with an `InstantiateMsg`
+    pub fn instantiate(
+        code_id: u64,
+        msg: &InstantiateMsg,
+        label: impl Into<String>,
+        admin: Option<String>,
+        funds: Vec<Coin>,
+    ) -> StdResult<CosmosMsg> {{
+        Ok(WasmMsg::Instantiate {{
+            admin,
+            code_id,
+            msg: {serializer}(msg)?,
+            funds,
+            label: label.into(),
+        }}
+        .into())
+    }}
+"
+        ));
+    }
+    if let Some(execute) = &execute {
+        body.push_str(&format!(
+            "
+    /// `ExecuteMsg` wrapped into a `WasmMsg::Execute` to this contract
+    pub fn execute(&self, msg: &ExecuteMsg, funds: Vec<Coin>) -> StdResult<CosmosMsg> {{
+        Ok(WasmMsg::Execute {{
+            contract_addr: self.0.to_string(),
+            msg: {serializer}(msg)?,
+            funds,
+        }}
+        .into())
+    }}
+"
+        ));
+        for variant in contract_schema::message_variants(execute) {
+            let method = match RESERVED.contains(&variant.name.as_str()) {
+                true => format!("{}_msg", variant.name),
+                false => variant.name.clone(),
+            };
+            body.push_str(&match construct(&variant, "ExecuteMsg") {
+                Some((params, msg)) => {
+                    signatures.push_str(&params);
+                    format!(
+                        "
+{}    pub fn {}(&self{}) -> StdResult<CosmosMsg> {{
+        self.execute(&{}, vec![])
+    }}
+",
+                        doc(&variant, "ExecuteMsg"),
+                        identifier(&method),
+                        params,
+                        msg
+                    )
+                }
+                None => skipped(&variant),
+            });
+        }
+    }
+    if let Some(query) = &query {
+        for variant in contract_schema::message_variants(query) {
+            let response = responses.get(&variant.name).and_then(response_type);
+            body.push_str(&match (construct(&variant, "QueryMsg"), response) {
+                (Some((params, msg)), Some(response)) => {
+                    signatures.push_str(&format!("{} {}", params, response));
+                    format!(
+                        "
+{}    pub fn query_{}<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>{},
+    ) -> StdResult<{}> {{
+        querier.query_wasm_smart(self.0.to_string(), &{})
+    }}
+",
+                        doc(&variant, "QueryMsg"),
+                        variant.name,
+                        params,
+                        response,
+                        msg
+                    )
+                }
+                _ => skipped(&variant),
+            });
+        }
+    }
+
+    let used: BTreeSet<&str> = signatures
+        .split(|x: char| !x.is_ascii_alphanumeric() && x != '_')
+        .collect();
+    imports.extend(STD_TYPES.iter().filter(|x| used.contains(*x)));
+    let mut imports: Vec<&str> = imports.into_iter().collect();
+    imports.sort_by_key(|x| x.to_lowercase());
+
+    Ok(format!(
+        "//! Generated by `warp schema` from the schemas of '{name}', do not edit
+
+#![allow(unused_imports)]
+
+use cosmwasm_std::{{{imports}}};
+
+use super::msg::*;
+
+/// Address of a deployed `{name}` contract, with typed message builders
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct {wrapper}(pub Addr);
+
+impl {wrapper} {{
+    pub fn new(addr: Addr) -> Self {{
+        Self(addr)
+    }}
+
+    pub fn addr(&self) -> &Addr {{
+        &self.0
+    }}
+{body}}}
+",
+        imports = imports.join(", "),
+    ))
+}
+
+fn skipped(variant: &MessageVariant) -> String {
+    format!(
+        "
+    // `{}` has types Warp can't recover from the schema; build it by hand
+",
+        variant.name
+    )
+}
+
+/// Doc comment of a builder: the variant path, followed by its schema description
+fn doc(variant: &MessageVariant, msg_type: &str) -> String {
+    let mut doc = format!("    /// `{}::{}`\n", msg_type, pascal_case(&variant.name));
+    if let Some(description) = &variant.description {
+        doc.push_str("    ///\n");
+        for line in description.lines() {
+            doc.push_str(format!("    /// {}", line).trim_end());
+            doc.push('\n');
+        }
+    }
+    doc
+}
+
+/// Extra method parameters and the message expression of a variant, `None` if one of its
+/// fields has a type that can't be recovered from the schema
+fn construct(variant: &MessageVariant, message: &str) -> Option<(String, String)> {
+    let path = format!("{}::{}", message, pascal_case(&variant.name));
+    let Some(fields) = &variant.fields else {
+        return Some((String::new(), path));
+    };
+    let properties = fields.get("properties").and_then(Value::as_object);
+    match properties {
+        Some(properties) => {
+            let mut params = String::new();
+            let mut names = vec![];
+            for (field, schema) in properties.iter() {
+                let field = identifier(field);
+                params.push_str(&format!(", {}: {}", field, rust_type(schema)?));
+                names.push(field);
+            }
+            Some((params, format!("{} {{ {} }}", path, names.join(", "))))
+        }
+        None if fields.get("type").and_then(Value::as_str) == Some("object") => {
+            Some((String::new(), format!("{} {{}}", path)))
+        }
+        None => Some((
+            format!(", msg: {}", rust_type(fields)?),
+            format!("{}(msg)", path),
+        )),
+    }
+}
+
+/// Return type of a query from its response schema: the schema title for named types,
+/// the structure for generic ones like `Array_of_String` and `Nullable_Addr`
+fn response_type(schema: &Value) -> Option<String> {
+    let title = schema
+        .get("title")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let named = title.starts_with(|x: char| x.is_ascii_uppercase())
+        && title.chars().all(|x| x.is_ascii_alphanumeric())
+        && !["Boolean", "String"].contains(&title);
+    match named {
+        true => Some(title.to_owned()),
+        false => rust_type(schema),
+    }
+}
+
+/// Rust type of a field schema as `cosmwasm_schema` writes it
+fn rust_type(schema: &Value) -> Option<String> {
+    let object = schema.as_object()?;
+    if let Some(reference) = object.get("$ref").and_then(Value::as_str) {
+        return reference.rsplit('/').next().map(str::to_owned);
+    }
+    if let Some(Value::Array(all)) = object.get("allOf") {
+        return match &all[..] {
+            [single] => rust_type(single),
+            _ => None,
+        };
+    }
+    if let Some(Value::Array(any)) = object.get("anyOf") {
+        let not_null: Vec<&Value> = any
+            .iter()
+            .filter(|x| x.get("type").and_then(Value::as_str) != Some("null"))
+            .collect();
+        return match not_null[..] {
+            [inner] if not_null.len() < any.len() => Some(format!("Option<{}>", rust_type(inner)?)),
+            _ => None,
+        };
+    }
+    match object.get("type")? {
+        Value::Array(types) => {
+            let not_null: Vec<&str> = types
+                .iter()
+                .filter_map(Value::as_str)
+                .filter(|x| *x != "null")
+                .collect();
+            match not_null[..] {
+                [single] if not_null.len() < types.len() => {
+                    Some(format!("Option<{}>", single_type(single, object)?))
+                }
+                [single] => single_type(single, object),
+                _ => None,
+            }
+        }
+        Value::String(single) => single_type(single, object),
+        _ => None,
+    }
+}
+
+fn single_type(type_name: &str, schema: &serde_json::Map<String, Value>) -> Option<String> {
+    let format = schema.get("format").and_then(Value::as_str);
+    match type_name {
+        "string" => Some("String".to_owned()),
+        "boolean" => Some("bool".to_owned()),
+        "integer" => Some(
+            match format {
+                Some("uint8") => "u8",
+                Some("uint16") => "u16",
+                Some("uint32") => "u32",
+                Some("uint128") => "u128",
+                Some("int8") => "i8",
+                Some("int16") => "i16",
+                Some("int32") => "i32",
+                Some("int64") => "i64",
+                Some("int128") => "i128",
+                _ => "u64",
+            }
+            .to_owned(),
+        ),
+        "number" => Some(
+            match format {
+                Some("float") => "f32",
+                _ => "f64",
+            }
+            .to_owned(),
+        ),
+        "array" => match schema.get("items")? {
+            Value::Array(items) => {
+                let items: Option<Vec<String>> = items.iter().map(rust_type).collect();
+                Some(format!("({})", items?.join(", ")))
+            }
+            items => Some(format!("Vec<{}>", rust_type(items)?)),
+        },
+        _ => None,
+    }
+}
+
+/// Field and method names, escaping keywords
+fn identifier(name: &str) -> String {
+    match KEYWORDS.contains(&name) {
+        true => format!("r#{}", name),
+        false => name.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn recovers_rust_types() {
+        assert_eq!(
+            rust_type(&json!({ "anyOf": [{ "$ref": "#/definitions/Addr" }, { "type": "null" }] })),
+            Some("Option<Addr>".to_owned())
+        );
+        assert_eq!(
+            rust_type(
+                &json!({ "type": "array", "items": { "type": ["integer", "null"], "format": "uint32" } })
+            ),
+            Some("Vec<Option<u32>>".to_owned())
+        );
+        assert_eq!(
+            rust_type(&json!({ "type": "object", "additionalProperties": true })),
+            None
+        );
+        assert_eq!(
+            response_type(
+                &json!({ "title": "Array_of_String", "type": "array", "items": { "type": "string" } })
+            ),
+            Some("Vec<String>".to_owned())
+        );
+        assert_eq!(
+            response_type(&json!({ "title": "OwnerResponse", "type": "object" })),
+            Some("OwnerResponse".to_owned())
+        );
+    }
+
+    #[test]
+    fn builds_variant_constructors() {
+        let variant = |name: &str, fields| MessageVariant {
+            name: name.to_owned(),
+            fields,
+            description: None,
+        };
+        assert_eq!(
+            construct(
+                &variant(
+                    "change_owner",
+                    Some(
+                        json!({ "type": "object", "properties": { "addr": { "$ref": "#/definitions/Addr" }, "type": { "type": "string" } } })
+                    )
+                ),
+                "ExecuteMsg"
+            ),
+            Some((
+                ", addr: Addr, r#type: String".to_owned(),
+                "ExecuteMsg::ChangeOwner { addr, r#type }".to_owned()
+            ))
+        );
+        assert_eq!(
+            construct(
+                &variant("get_owner", Some(json!({ "type": "object" }))),
+                "QueryMsg"
+            ),
+            Some((String::new(), "QueryMsg::GetOwner {}".to_owned()))
+        );
+        assert_eq!(
+            construct(&variant("reset", None), "ExecuteMsg"),
+            Some((String::new(), "ExecuteMsg::Reset".to_owned()))
+        );
+    }
+
+    #[test]
+    fn imports_referenced_std_types() {
+        let root = std::env::temp_dir().join(format!("warp-interface-{}", std::process::id()));
+        let contract = root.join("contracts").join("vault");
+        fs::create_dir_all(contract.join("schema")).unwrap();
+        fs::write(
+            contract.join("schema").join("vault.json"),
+            r##"{
+                "contract_name": "vault",
+                "execute": {
+                    "title": "ExecuteMsg",
+                    "oneOf": [{
+                        "type": "object",
+                        "required": ["deposit"],
+                        "properties": { "deposit": {
+                            "type": "object",
+                            "required": ["amount", "memo"],
+                            "properties": {
+                                "amount": { "$ref": "#/definitions/Uint128" },
+                                "memo": { "$ref": "#/definitions/Binary" }
+                            }
+                        } }
+                    }],
+                    "definitions": { "Binary": { "type": "string" }, "Uint128": { "type": "string" } }
+                }
+            }"##,
+        )
+        .unwrap();
+        let schema = ContractSchema::load_dir(&contract, false).unwrap().unwrap();
+        let legacy = generate("vault", &schema, "to_binary").unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(legacy.contains(
+            "use cosmwasm_std::{Addr, Binary, Coin, CosmosMsg, StdResult, to_binary, Uint128, WasmMsg};"
+        ));
+        assert!(legacy.contains("msg: to_binary(msg)?,"));
+        assert!(!legacy.contains("to_json_binary"));
+    }
+}
@@ -0,0 +1,594 @@
+//! Compatibility check between two versions of a contract's message schemas. Messages
+//! flow into the contract, so anything an old client may still send has to stay valid;
+//! query responses flow out, so anything an old client may read has to stay there.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use serde_json::{Map, Value};
+
+use crate::error::WarpError;
+
+use super::{
+    contract_schema::{self, ContractSchema, MessageKind},
+    file_util,
+};
+
+/// `--against` value that compares with the schemas recorded by the last deployment
+pub const DEPLOYMENT_BASELINE: &str = "deployment";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Compatibility {
+    Breaking,
+    Compatible,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemaChange {
+    pub compatibility: Compatibility,
+    /// Where the change is, e.g. `ExecuteMsg::transfer.amount`
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Whether values of a schema are sent to the contract or returned by it
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Direction {
+    Input,
+    Output,
+}
+
+/// Schemas to compare the current ones with
+pub enum Baseline {
+    /// The contracts' schemas at a git revision
+    Git { revision: String, workdir: PathBuf },
+    /// The snapshot `warp deploy` took for a Deployment.toml network
+    Deployment(PathBuf),
+}
+
+impl Baseline {
+    pub fn git(revision: &str) -> Self {
+        Self::Git {
+            revision: revision.to_owned(),
+            workdir: std::env::temp_dir().join(format!("warp-schema-diff-{}", std::process::id())),
+        }
+    }
+
+    pub fn deployment(project_root: &Path, deployment_key: &str) -> Self {
+        Self::Deployment(snapshot_dir(project_root, deployment_key))
+    }
+
+    /// Schema of the contract `name` in the baseline, `None` if it had none
+    pub fn load(
+        &self,
+        project_root: &Path,
+        name: &str,
+    ) -> Result<Option<ContractSchema>, WarpError> {
+        match self {
+            Self::Deployment(dir) => Ok(dir
+                .join(name)
+                .exists()
+                .then(|| ContractSchema::load_dir(&dir.join(name), false))
+                .transpose()?
+                .flatten()),
+            Self::Git { revision, workdir } => {
+                // The contract's own schema/ directory, or its copy in the workspace tree
+                let sources = [
+                    format!("contracts/{}/schema", name),
+                    format!("schema/{}", name),
+                ];
+                for source in sources {
+                    let files = git(
+                        project_root,
+                        &["ls-tree", "-r", "--name-only", revision, "--", &source],
+                    )?;
+                    if files.trim().is_empty() {
+                        continue;
+                    }
+                    let target = workdir.join(name).join("schema");
+                    if target.exists() {
+                        fs::remove_dir_all(&target)?;
+                    }
+                    for file in files.lines() {
+                        let relative = file
+                            .strip_prefix(&source)
+                            .unwrap_or(file)
+                            .trim_start_matches('/');
+                        let path = target.join(relative);
+                        fs::create_dir_all(path.parent().unwrap_or(&target))?;
+                        let content =
+                            git(project_root, &["show", &format!("{}:./{}", revision, file)])?;
+                        fs::write(path, content)?;
+                    }
+                    return ContractSchema::load_dir(&workdir.join(name), false);
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Removes the files checked out from git
+    pub fn cleanup(&self) -> Result<(), WarpError> {
+        if let Self::Git { workdir, .. } = self {
+            if workdir.exists() {
+                fs::remove_dir_all(workdir)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `<project>/.warp/schemas/<network>/`, where `warp deploy` keeps the schemas of the
+/// contracts it instantiated or migrated
+pub fn snapshot_dir(project_root: &Path, deployment_key: &str) -> PathBuf {
+    project_root
+        .join(".warp")
+        .join("schemas")
+        .join(deployment_key.replace('/', "_"))
+}
+
+/// Records the schemas of the contract `name` as deployed to `deployment_key`
+pub fn snapshot(
+    project_root: &Path,
+    deployment_key: &str,
+    name: &str,
+    schema: &ContractSchema,
+) -> Result<(), WarpError> {
+    let target = snapshot_dir(project_root, deployment_key)
+        .join(name)
+        .join("schema");
+    if target.exists() {
+        fs::remove_dir_all(&target)?;
+    }
+    file_util::copy_dir(schema.dir(), &target)?;
+    Ok(())
+}
+
+fn git(project_root: &Path, args: &[&str]) -> Result<String, WarpError> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(project_root)
+        .output()?;
+    if !output.status.success() {
+        return Err(WarpError::GitFailed(
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Changes from the `old` to the `new` schemas of a contract, breaking ones first
+pub fn diff_contract(
+    old: &ContractSchema,
+    new: &ContractSchema,
+) -> Result<Vec<SchemaChange>, WarpError> {
+    let mut changes = vec![];
+    for kind in MessageKind::ALL {
+        let path = kind.type_name();
+        match (old.message_schema(kind)?, new.message_schema(kind)?) {
+            (Some(old), Some(new)) => {
+                Differ::new(&old, &new, Direction::Input, &mut changes).compare(path, &old, &new)
+            }
+            (Some(_), None) => changes.push(SchemaChange {
+                compatibility: Compatibility::Breaking,
+                path: path.to_owned(),
+                message: "message removed".to_owned(),
+            }),
+            (None, Some(_)) => changes.push(SchemaChange {
+                compatibility: Compatibility::Compatible,
+                path: path.to_owned(),
+                message: "message added".to_owned(),
+            }),
+            (None, None) => (),
+        }
+    }
+    let new_responses = new.responses()?;
+    for (query, old) in old.responses()? {
+        if let Some(new) = new_responses.get(&query) {
+            let path = format!("QueryMsg::{} (response)", query);
+            Differ::new(&old, new, Direction::Output, &mut changes).compare(&path, &old, new);
+        }
+    }
+    changes.sort_by_key(|x| x.compatibility);
+    Ok(changes)
+}
+
+struct Differ<'a> {
+    old_root: &'a Value,
+    new_root: &'a Value,
+    direction: Direction,
+    /// Pairs of definitions already compared, so recursive types terminate
+    visited: BTreeSet<(String, String)>,
+    out: &'a mut Vec<SchemaChange>,
+}
+
+impl<'a> Differ<'a> {
+    fn new(
+        old_root: &'a Value,
+        new_root: &'a Value,
+        direction: Direction,
+        out: &'a mut Vec<SchemaChange>,
+    ) -> Self {
+        Self {
+            old_root,
+            new_root,
+            direction,
+            visited: BTreeSet::new(),
+            out,
+        }
+    }
+
+    /// Records a change that is breaking when it narrows what the contract accepts (for
+    /// messages) or widens what it may return (for responses)
+    fn narrowed(&mut self, path: &str, message: String, narrows_input: bool) {
+        let breaking = match self.direction {
+            Direction::Input => narrows_input,
+            Direction::Output => !narrows_input,
+        };
+        self.push(breaking, path, message);
+    }
+
+    fn push(&mut self, breaking: bool, path: &str, message: String) {
+        self.out.push(SchemaChange {
+            compatibility: match breaking {
+                true => Compatibility::Breaking,
+                false => Compatibility::Compatible,
+            },
+            path: path.to_owned(),
+            message,
+        });
+    }
+
+    fn compare(&mut self, path: &str, old: &Value, new: &Value) {
+        let (old, new) = (unwrap_all_of(old), unwrap_all_of(new));
+        if let (Some(old_ref), Some(new_ref)) = (reference(old), reference(new)) {
+            if !self
+                .visited
+                .insert((old_ref.to_owned(), new_ref.to_owned()))
+            {
+                return;
+            }
+        }
+        let (old, old_nullable) = contract_schema::nullable(deref(self.old_root, old));
+        let (new, new_nullable) = contract_schema::nullable(deref(self.new_root, new));
+        if old_nullable != new_nullable {
+            let message = match new_nullable {
+                true => "now accepts null",
+                false => "no longer accepts null",
+            };
+            self.narrowed(path, message.to_owned(), old_nullable);
+        }
+        // The non-null alternative of an `Option<T>` may itself be a `$ref` or documented
+        // wrapper. Anything that doesn't resolve further (a dangling `$ref`, an `allOf` of
+        // several schemas) is compared as it is below.
+        let resolves = |root, x: &Value| !std::ptr::eq(deref(root, unwrap_all_of(x)), x);
+        if resolves(self.old_root, old) || resolves(self.new_root, new) {
+            return self.compare(path, old, new);
+        }
+
+        let old_variants = contract_schema::message_variants(old);
+        let new_variants = contract_schema::message_variants(new);
+        if !old_variants.is_empty() && !new_variants.is_empty() {
+            return self.compare_variants(path, old_variants, new_variants);
+        }
+        if let (Some(old_values), Some(new_values)) = (enum_values(old), enum_values(new)) {
+            for value in old_values.difference(&new_values) {
+                self.narrowed(path, format!("value {} removed", value), true);
+            }
+            for value in new_values.difference(&old_values) {
+                self.narrowed(path, format!("value {} added", value), false);
+            }
+            return;
+        }
+        if let (Some(old_properties), Some(new_properties)) = (properties(old), properties(new)) {
+            return self.compare_properties(path, (old, old_properties), (new, new_properties));
+        }
+        if let (Some(old_items), Some(new_items)) = (old.get("items"), new.get("items")) {
+            if old_items.is_object() && new_items.is_object() && label(old) == label(new) {
+                return self.compare(&format!("{}[]", path), old_items, new_items);
+            }
+        }
+        if let (Some(old_values @ Value::Object(_)), Some(new_values @ Value::Object(_))) = (
+            old.get("additionalProperties"),
+            new.get("additionalProperties"),
+        ) {
+            return self.compare(&format!("{}{{}}", path), old_values, new_values);
+        }
+        let (old_label, new_label) = (label(old), label(new));
+        if old_label != new_label {
+            self.push(
+                true,
+                path,
+                format!("type changed from {} to {}", old_label, new_label),
+            );
+        }
+    }
+
+    fn compare_variants(
+        &mut self,
+        path: &str,
+        old: Vec<contract_schema::MessageVariant>,
+        new: Vec<contract_schema::MessageVariant>,
+    ) {
+        let new: BTreeMap<String, Option<Value>> =
+            new.into_iter().map(|x| (x.name, x.fields)).collect();
+        let old: BTreeMap<String, Option<Value>> =
+            old.into_iter().map(|x| (x.name, x.fields)).collect();
+        for (name, old_fields) in old.iter() {
+            let variant_path = format!("{}::{}", path, name);
+            match (old_fields, new.get(name)) {
+                (_, None) => self.narrowed(&variant_path, "variant removed".to_owned(), true),
+                (Some(old_fields), Some(Some(new_fields))) => {
+                    self.compare(&variant_path, old_fields, new_fields)
+                }
+                (None, Some(None)) => (),
+                (_, Some(_)) => self.push(
+                    true,
+                    &variant_path,
+                    "changed between a unit and a struct variant".to_owned(),
+                ),
+            }
+        }
+        for name in new.keys().filter(|x| !old.contains_key(*x)) {
+            let variant_path = format!("{}::{}", path, name);
+            self.narrowed(&variant_path, "variant added".to_owned(), false);
+        }
+    }
+
+    fn compare_properties(
+        &mut self,
+        path: &str,
+        (old, old_properties): (&Value, &Map<String, Value>),
+        (new, new_properties): (&Value, &Map<String, Value>),
+    ) {
+        let (old_required, new_required) = (required(old), required(new));
+        for (field, old_schema) in old_properties.iter() {
+            let field_path = format!("{}.{}", path, field);
+            let Some(new_schema) = new_properties.get(field) else {
+                // Old clients keep sending it, and `cw_serde` rejects unknown fields
+                self.push(true, &field_path, "field removed".to_owned());
+                continue;
+            };
+            match (
+                old_required.contains(field.as_str()),
+                new_required.contains(field.as_str()),
+            ) {
+                (false, true) => {
+                    self.narrowed(&field_path, "field is now required".to_owned(), true)
+                }
+                (true, false) => {
+                    self.narrowed(&field_path, "field is now optional".to_owned(), false)
+                }
+                _ => (),
+            }
+            self.compare(&field_path, old_schema, new_schema);
+        }
+        for (field, _) in new_properties
+            .iter()
+            .filter(|x| !old_properties.contains_key(x.0))
+        {
+            let field_path = format!("{}.{}", path, field);
+            match new_required.contains(field.as_str()) {
+                true => self.narrowed(&field_path, "required field added".to_owned(), true),
+                false => self.narrowed(&field_path, "optional field added".to_owned(), false),
+            }
+        }
+    }
+}
+
+fn reference(schema: &Value) -> Option<&str> {
+    schema.get("$ref").and_then(Value::as_str)
+}
+
+fn deref<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+    match reference(schema)
+        .and_then(|x| x.strip_prefix('#'))
+        .and_then(|x| root.pointer(x))
+    {
+        Some(target) => deref(root, unwrap_all_of(target)),
+        None => schema,
+    }
+}
+
+/// The only schema of a single-element `allOf`, which is how schemars documents a `$ref` field:
+/// `{ "description": …, "allOf": [{ "$ref": … }] }`. The annotations are dropped with the wrapper.
+fn unwrap_all_of(schema: &Value) -> &Value {
+    match schema
+        .get("allOf")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+    {
+        Some([single]) => unwrap_all_of(single),
+        _ => schema,
+    }
+}
+
+fn enum_values(schema: &Value) -> Option<BTreeSet<String>> {
+    let values = schema.get("enum").and_then(Value::as_array)?;
+    Some(values.iter().map(|x| x.to_string()).collect())
+}
+
+fn properties(schema: &Value) -> Option<&Map<String, Value>> {
+    schema.get("properties").and_then(Value::as_object)
+}
+
+fn required(schema: &Value) -> BTreeSet<&str> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect()
+}
+
+/// Short description of a type, e.g. `integer (uint64)`
+fn label(schema: &Value) -> String {
+    let types = match schema.get("type") {
+        Some(Value::String(name)) => name.clone(),
+        Some(Value::Array(names)) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .filter(|x| *x != "null")
+            .collect::<Vec<_>>()
+            .join(" | "),
+        _ if schema.get("properties").is_some() => "object".to_owned(),
+        _ => {
+            // Annotations don't change what a schema accepts
+            let mut schema = schema.clone();
+            if let Some(fields) = schema.as_object_mut() {
+                fields.remove("description");
+                fields.remove("title");
+            }
+            return schema.to_string();
+        }
+    };
+    match schema.get("format").and_then(Value::as_str) {
+        Some(format) => format!("{} ({})", types, format),
+        None => types,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn diff(old: &Value, new: &Value, direction: Direction) -> Vec<String> {
+        let mut changes = vec![];
+        Differ::new(old, new, direction, &mut changes).compare("ExecuteMsg", old, new);
+        changes
+            .iter()
+            .map(|x| format!("{:?} {}", x.compatibility, x))
+            .collect()
+    }
+
+    #[test]
+    fn classifies_message_and_response_changes() {
+        let old = json!({
+            "oneOf": [
+                { "type": "object", "required": ["transfer"], "properties": { "transfer": {
+                    "type": "object",
+                    "required": ["amount", "recipient"],
+                    "properties": {
+                        "amount": { "$ref": "#/definitions/Uint128" },
+                        "recipient": { "type": "string" },
+                        "memo": { "type": ["string", "null"] }
+                    }
+                } } },
+                { "type": "object", "required": ["burn"], "properties": { "burn": { "type": "object" } } }
+            ],
+            "definitions": { "Uint128": { "type": "string" } }
+        });
+        let new = json!({
+            "oneOf": [
+                { "type": "object", "required": ["transfer"], "properties": { "transfer": {
+                    "type": "object",
+                    "required": ["amount", "recipient", "fee"],
+                    "properties": {
+                        "amount": { "$ref": "#/definitions/Uint64" },
+                        "recipient": { "type": "string" },
+                        "fee": { "type": "integer", "format": "uint64" },
+                        "note": { "type": "string" }
+                    }
+                } } },
+                { "type": "object", "required": ["mint"], "properties": { "mint": { "type": "object" } } }
+            ],
+            "definitions": { "Uint64": { "type": "integer", "format": "uint64" } }
+        });
+        assert_eq!(
+            diff(&old, &new, Direction::Input),
+            [
+                "Breaking ExecuteMsg::burn: variant removed",
+                "Breaking ExecuteMsg::transfer.amount: type changed from string to integer (uint64)",
+                "Breaking ExecuteMsg::transfer.memo: field removed",
+                "Breaking ExecuteMsg::transfer.fee: required field added",
+                "Compatible ExecuteMsg::transfer.note: optional field added",
+                "Compatible ExecuteMsg::mint: variant added",
+            ]
+        );
+
+        let old = json!({ "type": "object", "required": ["count"], "properties": {
+            "count": { "type": "integer" }
+        } });
+        let new = json!({ "type": "object", "required": ["owner"], "properties": {
+            "count": { "type": "integer" },
+            "owner": { "type": "string" }
+        } });
+        assert_eq!(
+            diff(&old, &new, Direction::Output),
+            [
+                "Breaking ExecuteMsg.count: field is now optional",
+                "Compatible ExecuteMsg.owner: required field added",
+            ]
+        );
+    }
+
+    #[test]
+    fn follows_documented_references() {
+        let schema = |description: &str, required: Value| {
+            json!({
+                "type": "object",
+                "required": ["config"],
+                "properties": {
+                    "config": { "description": description, "allOf": [{ "$ref": "#/definitions/Config" }] },
+                    "owner": {
+                        "description": description,
+                        "anyOf": [{ "$ref": "#/definitions/Addr" }, { "type": "null" }]
+                    }
+                },
+                "definitions": {
+                    "Addr": { "description": description, "type": "string" },
+                    "Config": { "type": "object", "required": required, "properties": {
+                        "limit": { "type": "integer", "format": "uint32" },
+                        "fee": { "type": "integer", "format": "uint32" }
+                    } }
+                }
+            })
+        };
+        let old = schema("Contract settings", json!(["limit"]));
+        assert!(diff(
+            &old,
+            &schema("Settings", json!(["limit"])),
+            Direction::Input
+        )
+        .is_empty());
+        assert_eq!(
+            diff(
+                &old,
+                &schema("Settings", json!(["limit", "fee"])),
+                Direction::Input
+            ),
+            ["Breaking ExecuteMsg.config.fee: field is now required"]
+        );
+    }
+
+    #[test]
+    fn compares_unresolvable_schemas_as_they_are() {
+        let schema = |all_of: Value| {
+            json!({ "type": "object", "properties": {
+                "both": { "allOf": all_of },
+                "dangling": { "$ref": "#/definitions/Missing" },
+                "owner": { "anyOf": [{ "$ref": "#/definitions/Missing" }, { "type": "null" }] }
+            } })
+        };
+        let old = schema(json!([{ "$ref": "#/definitions/A" }, { "$ref": "#/definitions/B" }]));
+        let new = schema(json!([{ "$ref": "#/definitions/A" }, { "$ref": "#/definitions/C" }]));
+        assert!(diff(&old, &old, Direction::Input).is_empty());
+        let changes = diff(&old, &new, Direction::Input);
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].starts_with("Breaking ExecuteMsg.both: type changed"));
+    }
+}
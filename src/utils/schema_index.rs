@@ -0,0 +1,180 @@
+//! The workspace `schema/` tree written by `warp schema`: a copy of every contract's
+//! `schema/` directory under `schema/<contract>/`, plus an `index.json` manifest.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::WarpError;
+
+use super::{
+    contract_schema::{ContractSchema, MessageKind},
+    file_util,
+};
+
+pub const INDEX_FILE: &str = "index.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct SchemaIndex {
+    #[serde(default)]
+    pub contracts: BTreeMap<String, IndexedContract>,
+    /// Contracts without a schema generator
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing_generator: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct IndexedContract {
+    /// `package.version` of the contract's Cargo.toml
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Whether the schemas were just regenerated, as opposed to copied as they were
+    pub generated: bool,
+    /// Message types with a schema, e.g. `ExecuteMsg`
+    #[serde(default)]
+    pub messages: Vec<String>,
+    /// Files relative to `schema/`
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
+impl SchemaIndex {
+    pub fn dir(project_root: &Path) -> PathBuf {
+        project_root.join("schema")
+    }
+
+    /// The current manifest, empty if `warp schema` never ran
+    pub fn load(project_root: &Path) -> Result<Self, WarpError> {
+        let path = Self::dir(project_root).join(INDEX_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<PathBuf, WarpError> {
+        let dir = Self::dir(project_root);
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(INDEX_FILE);
+        fs::write(&path, format!("{:#}\n", serde_json::to_value(self)?))?;
+        Ok(path)
+    }
+
+    /// Replaces `schema/<name>/` with the contract's schemas and records them
+    pub fn collect(
+        &mut self,
+        project_root: &Path,
+        name: &str,
+        schema: &ContractSchema,
+        generated: bool,
+    ) -> Result<(), WarpError> {
+        let root = Self::dir(project_root);
+        let target = root.join(name);
+        if target.exists() {
+            fs::remove_dir_all(&target)?;
+        }
+        let mut files: Vec<String> = file_util::copy_dir(schema.dir(), &target)?
+            .iter()
+            .map(|x| {
+                x.strip_prefix(&root)
+                    .unwrap_or(x)
+                    .components()
+                    .map(|x| x.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .collect();
+        files.sort();
+        let mut messages = vec![];
+        for kind in MessageKind::ALL {
+            if schema.message_schema(kind)?.is_some() {
+                messages.push(kind.type_name().to_owned());
+            }
+        }
+        let contract_dir = project_root.join("contracts").join(name);
+        self.contracts.insert(
+            name.to_owned(),
+            IndexedContract {
+                version: package_version(project_root, &contract_dir),
+                generated,
+                messages,
+                files,
+            },
+        );
+        Ok(())
+    }
+
+    /// Drops `schema/<name>/` and its entry
+    pub fn remove(&mut self, project_root: &Path, name: &str) -> Result<(), WarpError> {
+        let target = Self::dir(project_root).join(name);
+        if target.exists() {
+            fs::remove_dir_all(target)?;
+        }
+        self.contracts.remove(name);
+        Ok(())
+    }
+}
+
+/// `package.version` of a contract, following `version.workspace = true` to the
+/// workspace Cargo.toml
+fn package_version(project_root: &Path, contract_dir: &Path) -> Option<String> {
+    let read = |path: PathBuf| -> Option<toml::Value> {
+        toml::from_str(&fs::read_to_string(path).ok()?).ok()
+    };
+    let manifest = read(contract_dir.join("Cargo.toml"))?;
+    match manifest.get("package")?.get("version")? {
+        toml::Value::String(version) => Some(version.clone()),
+        toml::Value::Table(x) if x.get("workspace") == Some(&toml::Value::Boolean(true)) => {
+            read(project_root.join("Cargo.toml"))?
+                .get("workspace")?
+                .get("package")?
+                .get("version")?
+                .as_str()
+                .map(str::to_owned)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_contract_schemas() {
+        let root = std::env::temp_dir().join(format!("warp-schema-index-{}", std::process::id()));
+        let contract = root.join("contracts").join("counter");
+        fs::create_dir_all(contract.join("schema").join("raw")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace.package]\nversion = \"1.2.0\"\n",
+        )
+        .unwrap();
+        fs::write(
+            contract.join("Cargo.toml"),
+            "[package]\nname = \"counter\"\nversion.workspace = true\n",
+        )
+        .unwrap();
+        fs::write(
+            contract.join("schema").join("raw").join("execute.json"),
+            r#"{"title": "ExecuteMsg", "oneOf": []}"#,
+        )
+        .unwrap();
+
+        let mut index = SchemaIndex::default();
+        let schema = ContractSchema::load_dir(&contract, false).unwrap().unwrap();
+        index.collect(&root, "counter", &schema, false).unwrap();
+        index.save(&root).unwrap();
+
+        let loaded = SchemaIndex::load(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        let counter = &loaded.contracts["counter"];
+        assert_eq!(counter.version.as_deref(), Some("1.2.0"));
+        assert_eq!(counter.messages, ["ExecuteMsg"]);
+        assert_eq!(counter.files, ["counter/raw/execute.json"]);
+    }
+}
@@ -0,0 +1,331 @@
+//! `{{ expression }}` templates in init, migrate and execute messages. An expression is a
+//! path, optionally followed by filters: `{{ steps.acl.code_id | number }}`.
+//!
+//! Paths:
+//! - `deployer` - the deployer address
+//! - `steps.<name>.address`, `.code_id`, `.label`, `.checksum` - values of an autodeploy
+//!   step, where `<name>` is the step id without its sigil (and optionally without the
+//!   leading underscores, so `$_acl` is both `steps._acl` and `steps.acl`)
+//! - `network.chain_id`, `.denom`, `.rpc_url`, `.lcd_url`, `.profile`
+//! - `env.<VAR>` - an environment variable
+//! - `now` - the current Unix time in seconds
+//!
+//! Filters: `number`, `string` and `json` (parses a string as JSON).
+//!
+//! An expression that makes up a whole JSON string (`"{{ ... }}"`) is replaced together
+//! with its quotes by the typed value, so `"{{ steps.acl.code_id | number }}"` becomes a
+//! JSON number. Inside a longer string the value is spliced in as text, and outside of
+//! strings it's written as JSON.
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use crate::error::WarpError;
+
+use super::{
+    deployment_graph::{self, ACCOUNT_PLACEHOLDER},
+    deployment_result::DeploymentRecord,
+    project_config::ProjectConfig,
+};
+
+/// What template expressions are evaluated against
+#[derive(Clone, Debug, Default)]
+pub struct TemplateContext {
+    /// Deployer address (the signer for `warp wasm execute`), if known
+    pub deployer: Option<String>,
+    pub network: BTreeMap<&'static str, Value>,
+    /// Known values per step reference (step id without its sigil)
+    pub steps: BTreeMap<String, StepContext>,
+    /// Show step values that don't exist yet as `<steps.name.field>`, keep values a
+    /// filter can't convert and allow the result to be invalid JSON, so plans can show
+    /// where the missing values go
+    pub lenient: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StepContext {
+    pub address: Option<String>,
+    pub code_id: Option<String>,
+    pub label: Option<String>,
+    pub checksum: Option<String>,
+}
+
+impl TemplateContext {
+    pub fn new(deployer: Option<&str>, config: &ProjectConfig) -> Self {
+        let network = &config.network;
+        Self {
+            deployer: deployer.map(str::to_owned),
+            network: BTreeMap::from([
+                ("profile", json!(network.profile)),
+                ("chain_id", json!(network.chain_id)),
+                ("rpc_url", json!(network.rpc_url)),
+                ("lcd_url", json!(network.lcd_url)),
+                ("denom", json!(network.denom)),
+            ]),
+            steps: BTreeMap::new(),
+            lenient: false,
+        }
+    }
+
+    /// Adds the deployed contracts of a Deployment.toml network as steps
+    pub fn with_deployments(mut self, records: &BTreeMap<String, DeploymentRecord>) -> Self {
+        for (id, record) in records.iter() {
+            self.steps.insert(
+                id.trim_start_matches(['$', '#']).to_owned(),
+                StepContext {
+                    address: Some(record.address.clone()),
+                    code_id: record.code_id.clone(),
+                    label: record.label.clone(),
+                    checksum: record.checksum.clone(),
+                },
+            );
+        }
+        self
+    }
+
+    /// Renders `msg` and checks that the result is valid JSON (unless lenient). The
+    /// `$name`/`#name` placeholders are replaced first.
+    pub fn render(&self, msg: &str) -> Result<String, WarpError> {
+        let msg = deployment_graph::render(msg, |sigil, name| {
+            if sigil == '$' && name == ACCOUNT_PLACEHOLDER {
+                return self.deployer.clone();
+            }
+            let step = self.steps.get(name)?;
+            match sigil {
+                '$' => step.address.clone(),
+                _ => step.code_id.clone(),
+            }
+        });
+        let rendered = self.render_templates(&msg)?;
+        if let Err(x) = serde_json::from_str::<Value>(&rendered) {
+            if !self.lenient {
+                return Err(WarpError::InvalidMessage(rendered, x.to_string()));
+            }
+        }
+        Ok(rendered)
+    }
+
+    fn render_templates(&self, msg: &str) -> Result<String, WarpError> {
+        let mut output = String::with_capacity(msg.len());
+        // Output position of the opening quote of the JSON string we're in
+        let mut string_start: Option<usize> = None;
+        let mut escaped = false;
+        let mut rest = msg;
+        while let Some(c) = rest.chars().next() {
+            if rest.starts_with("{{") {
+                let end = rest.find("}}").ok_or_else(|| {
+                    let expression: String = rest[2..].chars().take(40).collect();
+                    WarpError::TemplateError(
+                        expression.trim().to_owned(),
+                        "missing '}}'".to_owned(),
+                    )
+                })?;
+                let value = self.evaluate(&rest[2..end])?;
+                rest = &rest[end + 2..];
+                match string_start {
+                    Some(start) if start == output.len() - 1 && rest.starts_with('"') => {
+                        output.pop();
+                        output.push_str(&value.to_string());
+                        rest = &rest[1..];
+                        string_start = None;
+                    }
+                    Some(_) => {
+                        let text = match value {
+                            Value::String(x) => x,
+                            x => x.to_string(),
+                        };
+                        let quoted = Value::String(text).to_string();
+                        output.push_str(&quoted[1..quoted.len() - 1]);
+                    }
+                    None => output.push_str(&value.to_string()),
+                }
+                continue;
+            }
+            match c {
+                _ if escaped => escaped = false,
+                '\\' if string_start.is_some() => escaped = true,
+                '"' if string_start.is_some() => string_start = None,
+                '"' => string_start = Some(output.len()),
+                _ => (),
+            }
+            output.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+        Ok(output)
+    }
+
+    fn evaluate(&self, expression: &str) -> Result<Value, WarpError> {
+        let error = |reason: String| WarpError::TemplateError(expression.trim().to_owned(), reason);
+        let mut parts = expression.split('|').map(str::trim);
+        let path: Vec<&str> = parts.next().unwrap_or_default().split('.').collect();
+        let mut value = match path[..] {
+            ["deployer"] => match &self.deployer {
+                Some(deployer) => json!(deployer),
+                None if self.lenient => json!("<deployer>"),
+                None => return Err(error("the deployer address is unknown".to_owned())),
+            },
+            ["now"] => json!(chrono::Utc::now().timestamp()),
+            ["env", name] => json!(std::env::var(name)
+                .map_err(|_| error(format!("environment variable '{}' is not set", name)))?),
+            ["network", field] => self
+                .network
+                .get(field)
+                .cloned()
+                .ok_or_else(|| error(format!("unknown network field '{}'", field)))?,
+            ["steps", name, field] => {
+                let step = self
+                    .steps
+                    .iter()
+                    .find(|(reference, _)| *reference == name)
+                    .or_else(|| self.steps.iter().find(|(x, _)| matches_step(x, name)))
+                    .map(|(_, step)| step)
+                    .ok_or_else(|| error(format!("unknown step '{}'", name)))?;
+                let value = match field {
+                    "address" => &step.address,
+                    "code_id" => &step.code_id,
+                    "label" => &step.label,
+                    "checksum" => &step.checksum,
+                    _ => return Err(error(format!("unknown step field '{}'", field))),
+                };
+                match value {
+                    Some(value) => json!(value),
+                    None if self.lenient => json!(format!("<steps.{}.{}>", name, field)),
+                    None => return Err(error(format!("'{}' has no {} yet", name, field))),
+                }
+            }
+            _ => return Err(error("unknown path".to_owned())),
+        };
+        for filter in parts {
+            let converted = match (filter, &value) {
+                ("number", Value::Number(_)) | ("string", Value::String(_)) => Ok(value.clone()),
+                ("number", Value::String(x)) => serde_json::from_str::<serde_json::Number>(x)
+                    .map(Value::Number)
+                    .map_err(|_| format!("'{}' is not a number", x)),
+                ("string", x) => Ok(Value::String(x.to_string())),
+                ("json", Value::String(x)) => serde_json::from_str(x).map_err(|x| x.to_string()),
+                ("json", _) => Ok(value.clone()),
+                (filter, _) if !["number", "string", "json"].contains(&filter) => {
+                    return Err(error(format!("unknown filter '{}'", filter)))
+                }
+                (_, x) => Err(format!("can't convert {} with '{}'", x, filter)),
+            };
+            value = match converted {
+                Ok(x) => x,
+                Err(_) if self.lenient => value,
+                Err(reason) => return Err(error(reason)),
+            };
+        }
+        Ok(value)
+    }
+}
+
+/// Whether `name` in `steps.<name>` refers to the step with the given reference
+pub fn matches_step(reference: &str, name: &str) -> bool {
+    reference == name || reference.trim_start_matches('_') == name
+}
+
+/// `(field, name)` of every `steps.<name>.<field>` expression in `msg`
+pub fn step_references(msg: &str) -> Vec<(String, String)> {
+    msg.split("{{")
+        .skip(1)
+        .filter_map(|x| x.split("}}").next())
+        .filter_map(|expression| {
+            let path = expression.split('|').next()?.trim();
+            match path.split('.').collect::<Vec<_>>()[..] {
+                ["steps", name, field] => Some((field.to_owned(), name.to_owned())),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> TemplateContext {
+        let mut context = TemplateContext::new(Some("archway1deployer"), &ProjectConfig::empty());
+        context.network.insert("denom", json!("aconst"));
+        context.steps.insert(
+            "_acl".to_owned(),
+            StepContext {
+                address: Some("archway1acl".to_owned()),
+                code_id: Some("7".to_owned()),
+                ..Default::default()
+            },
+        );
+        context
+    }
+
+    #[test]
+    fn renders_typed_values() {
+        let rendered = context()
+            .render(
+                r#"{ "acl": "{{ steps.acl.address }}", "code": "{{ steps._acl.code_id | number }}",
+                     "raw": {{ steps.acl.code_id }}, "memo": "fee in {{network.denom}} \"x\"",
+                     "owner": "$account_id", "legacy": #_acl }"#,
+            )
+            .unwrap();
+        let value: Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "acl": "archway1acl",
+                "code": 7,
+                "raw": "7",
+                "memo": "fee in aconst \"x\"",
+                "owner": "archway1deployer",
+                "legacy": 7,
+            })
+        );
+    }
+
+    #[test]
+    fn reports_bad_expressions_and_invalid_json() {
+        let context = context();
+        for msg in [
+            r#"{ "a": "{{ steps.factory.address }}" }"#,
+            r#"{ "a": "{{ steps.acl.label }}" }"#,
+            r#"{ "a": "{{ deployer | number }}" }"#,
+            r#"{ "a": "{{ deployer | upper }}" }"#,
+        ] {
+            assert!(matches!(
+                context.render(msg),
+                Err(WarpError::TemplateError(..))
+            ));
+        }
+        assert!(matches!(
+            context.render(r#"{ "a": {{ deployer }}"#),
+            Err(WarpError::InvalidMessage(..))
+        ));
+
+        let lenient = TemplateContext {
+            lenient: true,
+            ..context
+        };
+        assert_eq!(
+            lenient
+                .render(r#"{"a":"{{ deployer | number }}"}"#)
+                .unwrap(),
+            r#"{"a":"archway1deployer"}"#
+        );
+        assert_eq!(
+            lenient.render(r#"{"a":#_factory}"#).unwrap(),
+            r#"{"a":#_factory}"#
+        );
+    }
+
+    #[test]
+    fn finds_step_references() {
+        assert_eq!(
+            step_references(
+                r#"{"a":"{{ steps.acl.address }}","b":{{steps._pair.code_id|number}}}"#
+            ),
+            vec![
+                ("address".to_owned(), "acl".to_owned()),
+                ("code_id".to_owned(), "_pair".to_owned())
+            ]
+        );
+    }
+}
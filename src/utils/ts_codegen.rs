@@ -0,0 +1,474 @@
+//! TypeScript types and CosmJS clients generated from a contract's JSON schemas, in the
+//! spirit of `@cosmwasm/ts-codegen`: one module per contract with an interface or type
+//! alias per message and definition, a `<Name>QueryClient` for `QueryMsg` and a
+//! `<Name>Client` that signs `ExecuteMsg`s.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::Value;
+
+use crate::error::WarpError;
+
+use super::contract_schema::{self, ContractSchema, MessageKind, MessageVariant};
+
+/// Source of the module for the contract `name` (its directory under `contracts/`)
+pub fn generate(name: &str, schema: &ContractSchema) -> Result<String, WarpError> {
+    let mut messages = vec![];
+    for kind in MessageKind::ALL {
+        if let Some(message) = schema.message_schema(kind)? {
+            messages.push((kind.type_name(), message));
+        }
+    }
+    let responses = schema.responses()?;
+    let definitions =
+        contract_schema::definitions(messages.iter().map(|x| &x.1).chain(responses.values()));
+
+    let mut module = Module::default();
+    for (type_name, message) in messages.iter() {
+        module.named_type(type_name, message);
+    }
+    let mut response_types = BTreeMap::new();
+    for (query, response) in responses.iter() {
+        let type_name = response
+            .get("title")
+            .and_then(Value::as_str)
+            .map(identifier)
+            .unwrap_or_else(|| format!("{}Response", pascal_case(query)));
+        module.named_type(&type_name, response);
+        response_types.insert(query.clone(), type_name);
+    }
+    for (type_name, definition) in definitions.iter() {
+        module.named_type(&identifier(type_name), definition);
+    }
+
+    let client_name = pascal_case(name);
+    let variants = |type_name: &str| {
+        messages
+            .iter()
+            .find(|x| x.0 == type_name)
+            .map(|x| contract_schema::message_variants(&x.1))
+            .unwrap_or_default()
+    };
+    let query = variants("QueryMsg");
+    let execute = variants("ExecuteMsg");
+    if !query.is_empty() {
+        module.query_client(&client_name, &query, &response_types);
+    }
+    if !execute.is_empty() {
+        module.client(&client_name, &execute, !query.is_empty());
+    }
+
+    let mut output = format!(
+        "// Generated by `warp codegen ts` from the schemas of '{}', do not edit\n\n",
+        name
+    );
+    let mut cosmwasm_imports = vec![];
+    if !query.is_empty() {
+        cosmwasm_imports.push("CosmWasmClient");
+    }
+    if !execute.is_empty() {
+        cosmwasm_imports.extend(["ExecuteResult", "SigningCosmWasmClient"]);
+        // A contract's own `Coin` has the same shape as the CosmJS one
+        let amino_imports: Vec<&str> = ["Coin", "StdFee"]
+            .into_iter()
+            .filter(|x| !module.emitted.contains(*x))
+            .collect();
+        if !amino_imports.is_empty() {
+            output.push_str(&format!(
+                "import {{ {} }} from \"@cosmjs/amino\";\n",
+                amino_imports.join(", ")
+            ));
+        }
+    }
+    if !cosmwasm_imports.is_empty() {
+        output.push_str(&format!(
+            "import {{ {} }} from \"@cosmjs/cosmwasm-stargate\";\n\n",
+            cosmwasm_imports.join(", ")
+        ));
+    }
+    output.push_str(&module.output);
+    Ok(output)
+}
+
+/// `index.ts` exporting every generated module as a namespace
+pub fn index(modules: &[String]) -> String {
+    let mut output = "// Generated by `warp codegen ts`, do not edit\n\n".to_owned();
+    for module in modules.iter() {
+        output.push_str(&format!(
+            "export * as {} from \"./{}\";\n",
+            pascal_case(module),
+            module
+        ));
+    }
+    output
+}
+
+#[derive(Default)]
+struct Module {
+    output: String,
+    /// Names of the types written so far
+    emitted: BTreeSet<String>,
+}
+
+impl Module {
+    /// Writes `schema` as an interface if it's an object with properties, and as a type
+    /// alias otherwise. Names that were written already are skipped.
+    fn named_type(&mut self, name: &str, schema: &Value) {
+        if !self.emitted.insert(name.to_owned()) {
+            return;
+        }
+        self.output.push_str(&doc_comment(schema, ""));
+        match schema.get("properties").and_then(Value::as_object) {
+            Some(properties) if !properties.is_empty() => {
+                self.output
+                    .push_str(&format!("export interface {} {{\n", name));
+                for (key, property) in properties.iter() {
+                    self.output.push_str(&doc_comment(property, "  "));
+                    self.output.push_str(&format!(
+                        "  {}{}: {};\n",
+                        property_key(key),
+                        if is_required(schema, key) { "" } else { "?" },
+                        ts_type(property)
+                    ));
+                }
+                self.output.push_str("}\n\n");
+            }
+            _ => self
+                .output
+                .push_str(&format!("export type {} = {};\n\n", name, ts_type(schema))),
+        }
+    }
+
+    fn query_client(
+        &mut self,
+        name: &str,
+        variants: &[MessageVariant],
+        response_types: &BTreeMap<String, String>,
+    ) {
+        self.output.push_str(&format!(
+            "export class {}QueryClient {{\n  client: CosmWasmClient;\n  contractAddress: string;\n\n",
+            name
+        ));
+        self.output.push_str(
+            "  constructor(client: CosmWasmClient, contractAddress: string) {\n    this.client = client;\n    this.contractAddress = contractAddress;\n  }\n",
+        );
+        for variant in variants.iter() {
+            let response = response_types
+                .get(&variant.name)
+                .map(String::as_str)
+                .unwrap_or("unknown");
+            let (params, msg) = method_arguments(variant);
+            self.output.push('\n');
+            self.output.push_str(&variant_comment(variant));
+            self.output.push_str(&format!(
+                "  async {}({}): Promise<{}> {{\n    return this.client.queryContractSmart(this.contractAddress, {});\n  }}\n",
+                camel_case(&variant.name),
+                params.join(", "),
+                response,
+                msg
+            ));
+        }
+        self.output.push_str("}\n\n");
+    }
+
+    fn client(&mut self, name: &str, variants: &[MessageVariant], extends_query: bool) {
+        if extends_query {
+            self.output.push_str(&format!(
+                "export class {0}Client extends {0}QueryClient {{\n  declare client: SigningCosmWasmClient;\n  sender: string;\n\n",
+                name
+            ));
+            self.output.push_str(
+                "  constructor(client: SigningCosmWasmClient, sender: string, contractAddress: string) {\n    super(client, contractAddress);\n    this.sender = sender;\n  }\n",
+            );
+        } else {
+            self.output.push_str(&format!(
+                "export class {}Client {{\n  client: SigningCosmWasmClient;\n  sender: string;\n  contractAddress: string;\n\n",
+                name
+            ));
+            self.output.push_str(
+                "  constructor(client: SigningCosmWasmClient, sender: string, contractAddress: string) {\n    this.client = client;\n    this.sender = sender;\n    this.contractAddress = contractAddress;\n  }\n",
+            );
+        }
+        for variant in variants.iter() {
+            let (mut params, msg) = method_arguments(variant);
+            params.extend([
+                "fee: StdFee | \"auto\" | number = \"auto\"".to_owned(),
+                "memo?: string".to_owned(),
+                "funds?: Coin[]".to_owned(),
+            ]);
+            self.output.push('\n');
+            self.output.push_str(&variant_comment(variant));
+            self.output.push_str(&format!(
+                "  async {}({}): Promise<ExecuteResult> {{\n    return this.client.execute(this.sender, this.contractAddress, {}, fee, memo, funds);\n  }}\n",
+                camel_case(&variant.name),
+                params.join(", "),
+                msg
+            ));
+        }
+        self.output.push_str("}\n\n");
+    }
+}
+
+/// Parameters of the client method for a variant, and the message it sends
+fn method_arguments(variant: &MessageVariant) -> (Vec<String>, String) {
+    let key = property_key(&variant.name);
+    match &variant.fields {
+        None => (vec![], serde_json::json!(variant.name).to_string()),
+        Some(fields) if is_empty_object(fields) => (vec![], format!("{{ {}: {{}} }}", key)),
+        Some(fields) => (
+            vec![format!("args: {}", ts_type(fields))],
+            format!("{{ {}: args }}", key),
+        ),
+    }
+}
+
+fn variant_comment(variant: &MessageVariant) -> String {
+    match &variant.description {
+        Some(description) => format!("  /** {} */\n", one_line(description)),
+        None => String::new(),
+    }
+}
+
+/// TypeScript type of a schema, written inline
+fn ts_type(schema: &Value) -> String {
+    let object = match schema {
+        Value::Bool(true) => return "unknown".to_owned(),
+        Value::Bool(false) => return "never".to_owned(),
+        Value::Object(object) => object,
+        _ => return "unknown".to_owned(),
+    };
+    if let Some(reference) = object.get("$ref").and_then(Value::as_str) {
+        return identifier(reference.rsplit('/').next().unwrap_or(reference));
+    }
+    if let Some(Value::Array(all)) = object.get("allOf") {
+        return join_types(all.iter().map(ts_type), " & ");
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(Value::Array(any)) = object.get(key) {
+            return join_types(any.iter().map(ts_type), " | ");
+        }
+    }
+    if let Some(Value::Array(values)) = object.get("enum") {
+        return join_types(values.iter().map(Value::to_string), " | ");
+    }
+    if let Some(value) = object.get("const") {
+        return value.to_string();
+    }
+    match object.get("type") {
+        Some(Value::Array(types)) => join_types(
+            types
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|x| single_type(x, schema)),
+            " | ",
+        ),
+        Some(Value::String(x)) => single_type(x, schema),
+        _ if object.contains_key("properties") => single_type("object", schema),
+        _ => "unknown".to_owned(),
+    }
+}
+
+fn single_type(type_name: &str, schema: &Value) -> String {
+    match type_name {
+        "string" => "string".to_owned(),
+        "integer" | "number" => "number".to_owned(),
+        "boolean" => "boolean".to_owned(),
+        "null" => "null".to_owned(),
+        "array" => match schema.get("items") {
+            Some(Value::Array(items)) => format!(
+                "[{}]",
+                items.iter().map(ts_type).collect::<Vec<_>>().join(", ")
+            ),
+            Some(items) => {
+                let item = ts_type(items);
+                match item.contains(' ') && !item.starts_with('{') {
+                    true => format!("({})[]", item),
+                    false => format!("{}[]", item),
+                }
+            }
+            None => "unknown[]".to_owned(),
+        },
+        "object" => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            match properties.filter(|x| !x.is_empty()) {
+                Some(properties) => {
+                    let fields: Vec<String> = properties
+                        .iter()
+                        .map(|(key, property)| {
+                            format!(
+                                "{}{}: {}",
+                                property_key(key),
+                                if is_required(schema, key) { "" } else { "?" },
+                                ts_type(property)
+                            )
+                        })
+                        .collect();
+                    format!("{{ {} }}", fields.join("; "))
+                }
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Object(values)) => {
+                        format!(
+                            "Record<string, {}>",
+                            ts_type(&Value::Object(values.clone()))
+                        )
+                    }
+                    Some(Value::Bool(false)) => "Record<string, never>".to_owned(),
+                    _ => "Record<string, unknown>".to_owned(),
+                },
+            }
+        }
+        _ => "unknown".to_owned(),
+    }
+}
+
+fn join_types(types: impl Iterator<Item = String>, separator: &str) -> String {
+    let mut unique: Vec<String> = vec![];
+    for x in types {
+        if !unique.contains(&x) {
+            unique.push(x);
+        }
+    }
+    match unique.len() {
+        0 => "never".to_owned(),
+        _ => unique.join(separator),
+    }
+}
+
+fn is_required(schema: &Value, key: &str) -> bool {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .is_some_and(|x| x.iter().any(|x| x == key))
+}
+
+/// An object schema without fields, like the `{}` of `{ "increment": {} }`
+fn is_empty_object(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("object")
+        && schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .is_none_or(|x| x.is_empty())
+        && !schema
+            .get("additionalProperties")
+            .is_some_and(Value::is_object)
+}
+
+fn doc_comment(schema: &Value, indent: &str) -> String {
+    match schema.get("description").and_then(Value::as_str) {
+        Some(description) => format!("{}/** {} */\n", indent, one_line(description)),
+        None => String::new(),
+    }
+}
+
+fn one_line(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("*/", "* /")
+}
+
+fn property_key(key: &str) -> String {
+    let valid = key
+        .chars()
+        .next()
+        .is_some_and(|x| x.is_ascii_alphabetic() || x == '_' || x == '$')
+        && key
+            .chars()
+            .all(|x| x.is_ascii_alphanumeric() || x == '_' || x == '$');
+    match valid {
+        true => key.to_owned(),
+        false => Value::from(key).to_string(),
+    }
+}
+
+/// Type names as valid identifiers
+fn identifier(name: &str) -> String {
+    name.chars()
+        .map(|x| match x.is_ascii_alphanumeric() {
+            true => x,
+            false => '_',
+        })
+        .collect()
+}
+
+/// `my_token` or `my-token` becomes `MyToken`
+pub fn pascal_case(name: &str) -> String {
+    name.split(|x: char| !x.is_ascii_alphanumeric())
+        .filter(|x| !x.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// `update_config` becomes `updateConfig`
+fn camel_case(name: &str) -> String {
+    let pascal = pascal_case(name);
+    let mut chars = pascal.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+        None => pascal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn writes_types_for_schemas() {
+        assert_eq!(
+            ts_type(&json!({ "type": ["string", "null"] })),
+            "string | null"
+        );
+        assert_eq!(
+            ts_type(&json!({ "anyOf": [{ "$ref": "#/definitions/Addr" }, { "type": "null" }] })),
+            "Addr | null"
+        );
+        assert_eq!(
+            ts_type(&json!({ "type": "array", "items": { "type": ["integer", "null"] } })),
+            "(number | null)[]"
+        );
+        assert_eq!(
+            ts_type(&json!({
+                "type": "object",
+                "required": ["owner"],
+                "properties": { "owner": { "type": "string" }, "fee-bps": { "type": "integer" } }
+            })),
+            r#"{ "fee-bps"?: number; owner: string }"#
+        );
+        assert_eq!(
+            ts_type(
+                &json!({ "type": "array", "items": [{ "type": "string" }, { "type": "integer" }] })
+            ),
+            "[string, number]"
+        );
+    }
+
+    #[test]
+    fn writes_client_methods_for_variants() {
+        let unit = MessageVariant {
+            name: "reset".to_owned(),
+            fields: None,
+            description: None,
+        };
+        assert_eq!(method_arguments(&unit), (vec![], r#""reset""#.to_owned()));
+        let empty = MessageVariant {
+            name: "get_count".to_owned(),
+            fields: Some(json!({ "type": "object" })),
+            description: None,
+        };
+        assert_eq!(
+            method_arguments(&empty),
+            (vec![], "{ get_count: {} }".to_owned())
+        );
+        assert_eq!(camel_case(&empty.name), "getCount");
+        assert_eq!(pascal_case("my-token"), "MyToken");
+    }
+}